@@ -1,8 +1,7 @@
-use anchor_lang::{prelude::*, solana_program};
-use solana_program::{
-    program::{invoke_signed},
-    system_instruction,
-};
+#![allow(unexpected_cfgs)]
+
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount};
 
 declare_id!("22222222222222222222222222222222222222222222");
 
@@ -10,35 +9,62 @@ declare_id!("22222222222222222222222222222222222222222222");
 pub mod blueshift_anchor_vault {
     use super::*;
 
+    // Create the optional accounting PDA for the signer's vault. Deposits and withdrawals made before it exists are
+    // not counted.
+    pub fn init_state(ctx: Context<InitState>) -> Result<()> {
+        let vault_state = &mut ctx.accounts.vault_state;
+        vault_state.owner = ctx.accounts.signer.key();
+        vault_state.total_deposited = 0;
+        vault_state.total_withdrawn = 0;
+        vault_state.last_activity = Clock::get()?.unix_timestamp;
+        vault_state.bump = ctx.bumps.vault_state;
+        Ok(())
+    }
+
     pub fn deposit(ctx: Context<VaultAction>, amount: u64) -> Result<()> {
         // Ensure amount is valid
         require_gt!(amount, 0, VaultError::InvalidAmount);
+        // The first deposit creates the vault, which must be rent exempt from the start.
+        let balance = ctx.accounts.vault.lamports().checked_add(amount).ok_or(VaultError::Overflow)?;
+        require_gte!(balance, Rent::get()?.minimum_balance(0), VaultError::BelowRentExempt);
 
-        // Transfer SOL from signer to vault PDA
-        let transfer_ix = system_instruction::transfer(
-            ctx.accounts.signer.key,
-            ctx.accounts.vault.key,
-            amount,
+        // Transfer SOL from signer to vault PDA. The signer already signed the transaction, so no seeds are needed.
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.signer.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+            },
         );
+        system_program::transfer(cpi_ctx, amount)?;
 
-        invoke_signed(
-            &transfer_ix,
-            &[
-                ctx.accounts.signer.to_account_info(),
-                ctx.accounts.vault.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            &[],
-        )?;
+        let timestamp = Clock::get()?.unix_timestamp;
+        if let Some(vault_state) = ctx.accounts.vault_state.as_mut() {
+            vault_state.total_deposited = vault_state
+                .total_deposited
+                .checked_add(amount)
+                .ok_or(VaultError::Overflow)?;
+            vault_state.last_activity = timestamp;
+        }
+        RecoveryConfig::touch_raw(&ctx.accounts.recovery, timestamp)?;
+        emit!(DepositEvent {
+            owner: ctx.accounts.signer.key(),
+            vault: ctx.accounts.vault.key(),
+            amount,
+            vault_balance: ctx.accounts.vault.lamports(),
+            timestamp,
+        });
 
         Ok(())
     }
 
-    pub fn withdraw(ctx: Context<VaultAction>) -> Result<()> {
+    pub fn withdraw(ctx: Context<VaultAction>, amount: u64) -> Result<()> {
         let vault_lamports = ctx.accounts.vault.lamports();
-        require_gt!(vault_lamports, 0, VaultError::InvalidAmount);
-    
-        // Build the seed slice correctly - note the different structure
+        require_gt!(vault_lamports, 0, VaultError::VaultNotFound);
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+        check_vault_debit(vault_lamports, amount)?;
+
+        // The vault PDA signs for itself with its seeds.
         let signer_seeds: &[&[&[u8]]] = &[
             &[
                 b"vault",
@@ -46,25 +72,328 @@ pub mod blueshift_anchor_vault {
                 &[ctx.bumps.vault],
             ],
         ];
-    
-        let transfer_ix = system_instruction::transfer(
-            ctx.accounts.vault.key,
-            ctx.accounts.signer.key,
-            vault_lamports,
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.signer.to_account_info(),
+            },
+            signer_seeds,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        if let Some(vault_state) = ctx.accounts.vault_state.as_mut() {
+            vault_state.total_withdrawn = vault_state
+                .total_withdrawn
+                .checked_add(amount)
+                .ok_or(VaultError::Overflow)?;
+            vault_state.last_activity = timestamp;
+        }
+        RecoveryConfig::touch_raw(&ctx.accounts.recovery, timestamp)?;
+        emit!(WithdrawEvent {
+            owner: ctx.accounts.signer.key(),
+            vault: ctx.accounts.vault.key(),
+            amount,
+            vault_balance: ctx.accounts.vault.lamports(),
+            timestamp,
+        });
+
+        Ok(())
+    }
+
+    // Configure (or replace) the recovery of the signer's vault. If the owner stays inactive for inactivity_period
+    // seconds, the guardian may start a recovery, and delay seconds later move the whole vault to the beneficiary.
+    pub fn set_recovery(
+        ctx: Context<SetRecovery>,
+        guardian: Pubkey,
+        beneficiary: Pubkey,
+        inactivity_period: i64,
+        delay: i64,
+    ) -> Result<()> {
+        require_gt!(inactivity_period, 0, VaultError::InvalidPeriod);
+        // Without a delay the guardian could start and execute in one transaction, leaving the owner no time to cancel.
+        require_gt!(delay, 0, VaultError::InvalidPeriod);
+        let recovery = &mut ctx.accounts.recovery;
+        recovery.owner = ctx.accounts.signer.key();
+        recovery.guardian = guardian;
+        recovery.beneficiary = beneficiary;
+        recovery.inactivity_period = inactivity_period;
+        recovery.delay = delay;
+        recovery.bump = ctx.bumps.recovery;
+        recovery.touch(Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+
+    // Remove the recovery configuration and return its rent to the owner.
+    pub fn remove_recovery(_ctx: Context<RemoveRecovery>) -> Result<()> {
+        Ok(())
+    }
+
+    // Called by the guardian once the owner has been inactive long enough.
+    pub fn start_recovery(ctx: Context<StartRecovery>) -> Result<()> {
+        let recovery = &mut ctx.accounts.recovery;
+        require_eq!(recovery.recovery_started_at, 0, VaultError::RecoveryAlreadyStarted);
+        let now = Clock::get()?.unix_timestamp;
+        let inactive_until = recovery
+            .last_activity
+            .checked_add(recovery.inactivity_period)
+            .ok_or(VaultError::Overflow)?;
+        require_gte!(now, inactive_until, VaultError::OwnerStillActive);
+        recovery.recovery_started_at = now;
+        Ok(())
+    }
+
+    // The owner can cancel a pending recovery at any time before it is executed. Any deposit or withdrawal has the
+    // same effect.
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        ctx.accounts.recovery.touch(Clock::get()?.unix_timestamp);
+        Ok(())
+    }
+
+    // After the delay, move everything in the vault to the beneficiary.
+    pub fn execute_recovery(ctx: Context<ExecuteRecovery>) -> Result<()> {
+        let recovery = &mut ctx.accounts.recovery;
+        require_neq!(recovery.recovery_started_at, 0, VaultError::RecoveryNotStarted);
+        let now = Clock::get()?.unix_timestamp;
+        let executable_at = recovery
+            .recovery_started_at
+            .checked_add(recovery.delay)
+            .ok_or(VaultError::Overflow)?;
+        require_gte!(now, executable_at, VaultError::RecoveryDelayNotElapsed);
+
+        let amount = ctx.accounts.vault.lamports();
+        require_gt!(amount, 0, VaultError::VaultNotFound);
+        let signer_seeds: &[&[&[u8]]] = &[
+            &[
+                b"vault",
+                ctx.accounts.owner.key.as_ref(),
+                &[ctx.bumps.vault],
+            ],
+        ];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.beneficiary.to_account_info(),
+            },
+            signer_seeds,
         );
-    
-        invoke_signed(
-            &transfer_ix,
+        system_program::transfer(cpi_ctx, amount)?;
+
+        recovery.recovery_started_at = 0;
+        if let Some(vault_state) = ctx.accounts.vault_state.as_mut() {
+            vault_state.total_withdrawn = vault_state
+                .total_withdrawn
+                .checked_add(amount)
+                .ok_or(VaultError::Overflow)?;
+            vault_state.last_activity = now;
+        }
+        emit!(WithdrawEvent {
+            owner: ctx.accounts.owner.key(),
+            vault: ctx.accounts.vault.key(),
+            amount,
+            vault_balance: ctx.accounts.vault.lamports(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    // Lock total lamports from the owner's vault into a stream that vests linearly to recipient between start and
+    // end. Nothing can be claimed before cliff. id lets one owner run several streams. For the accounting and the
+    // recovery this counts as a withdrawal from the vault.
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        id: u64,
+        recipient: Pubkey,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+    ) -> Result<()> {
+        ctx.accounts.stream.set_inner(Stream::new(
+            ctx.accounts.owner.key(),
+            recipient,
+            Pubkey::default(),
+            id,
+            total,
+            start,
+            end,
+            cliff,
+            ctx.bumps.stream,
+        )?);
+
+        let vault_lamports = ctx.accounts.vault.lamports();
+        require_gt!(vault_lamports, 0, VaultError::VaultNotFound);
+        check_vault_debit(vault_lamports, total)?;
+        let signer_seeds: &[&[&[u8]]] = &[
             &[
-                ctx.accounts.vault.to_account_info(),
-                ctx.accounts.signer.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
+                b"vault",
+                ctx.accounts.owner.key.as_ref(),
+                &[ctx.bumps.vault],
             ],
+        ];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.stream.to_account_info(),
+            },
             signer_seeds,
-        )?;
-    
+        );
+        system_program::transfer(cpi_ctx, total)?;
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        if let Some(vault_state) = ctx.accounts.vault_state.as_mut() {
+            vault_state.total_withdrawn = vault_state
+                .total_withdrawn
+                .checked_add(total)
+                .ok_or(VaultError::Overflow)?;
+            vault_state.last_activity = timestamp;
+        }
+        RecoveryConfig::touch_raw(&ctx.accounts.recovery, timestamp)?;
+        emit!(WithdrawEvent {
+            owner: ctx.accounts.owner.key(),
+            vault: ctx.accounts.vault.key(),
+            amount: total,
+            vault_balance: ctx.accounts.vault.lamports(),
+            timestamp,
+        });
         Ok(())
     }
+
+    // Pay the vested but unclaimed lamports to the recipient. Anyone may call it.
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let amount = ctx.accounts.stream.claim(Clock::get()?.unix_timestamp);
+        require_gt!(amount, 0, VaultError::NothingToClaim);
+        // The stream account is owned by this program, so its lamports can be moved directly.
+        **ctx.accounts.stream.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+        Ok(())
+    }
+
+    // Stop the stream: the recipient gets what has vested so far, the owner gets the unvested remainder and the
+    // account rent back.
+    pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
+        let amount = ctx.accounts.stream.claim(Clock::get()?.unix_timestamp);
+        if amount > 0 {
+            **ctx.accounts.stream.to_account_info().try_borrow_mut_lamports()? -= amount;
+            **ctx.accounts.recipient.to_account_info().try_borrow_mut_lamports()? += amount;
+        }
+        // `close = owner` moves the remaining lamports, unvested funds and rent, to the owner.
+        Ok(())
+    }
+
+    // Same as create_stream, for SPL tokens taken from the owner's token account. The tokens are held in a token
+    // account owned by the stream PDA.
+    pub fn create_token_stream(
+        ctx: Context<CreateTokenStream>,
+        id: u64,
+        recipient: Pubkey,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+    ) -> Result<()> {
+        ctx.accounts.stream.set_inner(Stream::new(
+            ctx.accounts.owner.key(),
+            recipient,
+            ctx.accounts.mint.key(),
+            id,
+            total,
+            start,
+            end,
+            cliff,
+            ctx.bumps.stream,
+        )?);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.owner_token_account.to_account_info(),
+                to: ctx.accounts.stream_token_account.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+            },
+        );
+        token::transfer(cpi_ctx, total)?;
+        Ok(())
+    }
+
+    pub fn claim_tokens(ctx: Context<ClaimTokens>) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+        let amount = stream.claim(Clock::get()?.unix_timestamp);
+        require_gt!(amount, 0, VaultError::NothingToClaim);
+        let id = stream.id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"stream", stream.owner.as_ref(), &id, &[stream.bump]]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::Transfer {
+                from: ctx.accounts.stream_token_account.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::transfer(cpi_ctx, amount)?;
+        Ok(())
+    }
+
+    pub fn cancel_token_stream(ctx: Context<CancelTokenStream>) -> Result<()> {
+        let stream = &mut ctx.accounts.stream;
+        let amount = stream.claim(Clock::get()?.unix_timestamp);
+        let id = stream.id.to_le_bytes();
+        let signer_seeds: &[&[&[u8]]] = &[&[b"stream", stream.owner.as_ref(), &id, &[stream.bump]]];
+        if amount > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.stream_token_account.to_account_info(),
+                    to: ctx.accounts.recipient_token_account.to_account_info(),
+                    authority: stream.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, amount)?;
+        }
+        // Everything left goes back to the owner: the unvested tokens and anything sent to the account directly, which
+        // would otherwise make close_account fail.
+        ctx.accounts.stream_token_account.reload()?;
+        let remainder = ctx.accounts.stream_token_account.amount;
+        if remainder > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::Transfer {
+                    from: ctx.accounts.stream_token_account.to_account_info(),
+                    to: ctx.accounts.owner_token_account.to_account_info(),
+                    authority: stream.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::transfer(cpi_ctx, remainder)?;
+        }
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.stream_token_account.to_account_info(),
+                destination: ctx.accounts.owner.to_account_info(),
+                authority: stream.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::close_account(cpi_ctx)?;
+        Ok(())
+    }
+}
+
+// Check that the vault holds amount and that taking it out leaves the vault either empty or rent exempt, otherwise the
+// runtime rejects the transaction with a much less helpful error.
+fn check_vault_debit(balance: u64, amount: u64) -> Result<()> {
+    require_gte!(balance, amount, VaultError::InsufficientFunds);
+    let remaining = balance - amount;
+    if remaining != 0 {
+        require_gte!(remaining, Rent::get()?.minimum_balance(0), VaultError::BelowRentExempt);
+    }
+    Ok(())
 }
 
 #[derive(Accounts)]
@@ -78,10 +407,426 @@ pub struct VaultAction<'info> {
     )]
     pub vault: SystemAccount<'info>, // Better to use SystemAccount instead of UncheckedAccount
     pub system_program: Program<'info, System>,
+    // Optional: pass the program id in its place to skip the accounting.
+    #[account(
+        mut,
+        seeds = [b"vault_state", signer.key().as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Option<Account<'info, VaultState>>,
+    /// CHECK: Always required, so that a deposit or withdrawal cannot skip the owner activity. It is only touched if
+    /// a recovery is configured, see `RecoveryConfig::touch_raw`.
+    #[account(
+        mut,
+        seeds = [b"recovery", signer.key().as_ref()],
+        bump,
+    )]
+    pub recovery: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitState<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + VaultState::INIT_SPACE,
+        seeds = [b"vault_state", signer.key().as_ref()],
+        bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecovery<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = 8 + RecoveryConfig::INIT_SPACE,
+        seeds = [b"recovery", signer.key().as_ref()],
+        bump,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveRecovery<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"recovery", signer.key().as_ref()],
+        bump = recovery.bump,
+        close = signer,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+#[derive(Accounts)]
+pub struct StartRecovery<'info> {
+    pub guardian: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"recovery", recovery.owner.as_ref()],
+        bump = recovery.bump,
+        has_one = guardian @ VaultError::Unauthorized,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"recovery", signer.key().as_ref()],
+        bump = recovery.bump,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    pub guardian: Signer<'info>,
+    /// CHECK: Only used as a seed; it must match the owner stored in the recovery account.
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"recovery", owner.key().as_ref()],
+        bump = recovery.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        has_one = guardian @ VaultError::Unauthorized,
+        has_one = beneficiary @ VaultError::Unauthorized,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+    #[account(mut)]
+    pub beneficiary: SystemAccount<'info>,
+    pub system_program: Program<'info, System>,
+    // Same as in `VaultAction`, the recovered amount counts as a withdrawal.
+    #[account(
+        mut,
+        seeds = [b"vault_state", owner.key().as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Option<Account<'info, VaultState>>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateStream<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Stream::INIT_SPACE,
+        seeds = [b"stream", owner.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stream: Account<'info, Stream>,
+    pub system_program: Program<'info, System>,
+    // Same as in `VaultAction`.
+    #[account(
+        mut,
+        seeds = [b"vault_state", owner.key().as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Option<Account<'info, VaultState>>,
+    /// CHECK: Same as in `VaultAction`.
+    #[account(
+        mut,
+        seeds = [b"recovery", owner.key().as_ref()],
+        bump,
+    )]
+    pub recovery: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(
+        mut,
+        seeds = [b"stream", stream.owner.as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump,
+        has_one = recipient @ VaultError::Unauthorized,
+        constraint = stream.mint == Pubkey::default() @ VaultError::WrongStreamKind,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Cancel<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"stream", owner.key().as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        has_one = recipient @ VaultError::Unauthorized,
+        constraint = stream.mint == Pubkey::default() @ VaultError::WrongStreamKind,
+        close = owner,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateTokenStream<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Stream::INIT_SPACE,
+        seeds = [b"stream", owner.key().as_ref(), id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"stream_tokens", stream.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = stream,
+    )]
+    pub stream_token_account: Account<'info, TokenAccount>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"stream", stream.owner.as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        mut,
+        seeds = [b"stream_tokens", stream.key().as_ref()],
+        bump,
+    )]
+    pub stream_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = stream.mint,
+        token::authority = stream.recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTokenStream<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"stream", owner.key().as_ref(), stream.id.to_le_bytes().as_ref()],
+        bump = stream.bump,
+        has_one = owner @ VaultError::Unauthorized,
+        close = owner,
+    )]
+    pub stream: Account<'info, Stream>,
+    #[account(
+        mut,
+        seeds = [b"stream_tokens", stream.key().as_ref()],
+        bump,
+    )]
+    pub stream_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = stream.mint,
+        token::authority = stream.recipient,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::mint = stream.mint,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Per-owner accounting, kept next to the [b"vault", owner] vault.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    pub owner: Pubkey,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub last_activity: i64, // Unix timestamp of the last deposit or withdrawal
+    pub bump: u8,
+}
+
+// Emergency recovery of the [b"vault", owner] vault, stored at [b"recovery", owner].
+#[account]
+#[derive(InitSpace)]
+pub struct RecoveryConfig {
+    pub owner: Pubkey,
+    pub guardian: Pubkey,          // May start and execute a recovery
+    pub beneficiary: Pubkey,       // Receives the vault balance
+    pub inactivity_period: i64,    // Seconds without owner activity before a recovery can start
+    pub delay: i64,                // Seconds between starting and executing a recovery
+    pub last_activity: i64,        // Unix timestamp of the last owner activity
+    pub recovery_started_at: i64,  // 0 when no recovery is pending
+    pub bump: u8,
+}
+
+impl RecoveryConfig {
+    // Owner activity restarts the inactivity period and cancels a pending recovery.
+    pub fn touch(&mut self, now: i64) {
+        self.last_activity = now;
+        self.recovery_started_at = 0;
+    }
+
+    // Same as `touch` for the [b"recovery", owner] account, which may not exist. Nothing happens if no recovery is
+    // configured.
+    pub fn touch_raw(info: &AccountInfo, now: i64) -> Result<()> {
+        if info.owner != &crate::ID || info.data_is_empty() {
+            return Ok(());
+        }
+        let mut data = info.try_borrow_mut_data()?;
+        let mut recovery = RecoveryConfig::try_deserialize(&mut &data[..])?;
+        recovery.touch(now);
+        recovery.try_serialize(&mut &mut data[..])?;
+        Ok(())
+    }
+}
+
+// A linear vesting stream, stored at [b"stream", owner, id]. Lamport streams hold their funds in this account, token
+// streams in the [b"stream_tokens", stream] token account.
+#[account]
+#[derive(InitSpace)]
+pub struct Stream {
+    pub owner: Pubkey,
+    pub recipient: Pubkey,
+    pub mint: Pubkey, // Pubkey::default() for a lamport stream
+    pub id: u64,
+    pub total: u64,
+    pub claimed: u64,
+    pub start: i64,
+    pub end: i64,
+    pub cliff: i64,
+    pub bump: u8,
+}
+
+impl Stream {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        owner: Pubkey,
+        recipient: Pubkey,
+        mint: Pubkey,
+        id: u64,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+        bump: u8,
+    ) -> Result<Self> {
+        require_gt!(total, 0, VaultError::InvalidAmount);
+        require!(start < end && start <= cliff && cliff <= end, VaultError::InvalidSchedule);
+        Ok(Self { owner, recipient, mint, id, total, claimed: 0, start, end, cliff, bump })
+    }
+
+    // Amount vested at time now.
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff {
+            return 0;
+        }
+        if now >= self.end {
+            return self.total;
+        }
+        let elapsed = (now - self.start) as u128;
+        let duration = (self.end - self.start) as u128;
+        (self.total as u128 * elapsed / duration) as u64
+    }
+
+    // Mark everything vested so far as claimed and return the newly claimable amount. vested() never goes below what
+    // was already claimed for a valid schedule, but a clock that moves backwards must not underflow: it just yields 0.
+    pub fn claim(&mut self, now: i64) -> u64 {
+        let amount = self.vested(now).saturating_sub(self.claimed);
+        self.claimed = self.claimed.saturating_add(amount);
+        amount
+    }
+}
+
+#[event]
+pub struct DepositEvent {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub owner: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub vault_balance: u64,
+    pub timestamp: i64,
 }
 
 #[error_code]
 pub enum VaultError {
     #[msg("Invalid amount")]
     InvalidAmount,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Vault does not exist, deposit first")]
+    VaultNotFound,
+    #[msg("Vault balance is lower than the withdrawal amount")]
+    InsufficientFunds,
+    #[msg("Vault balance would fall below the rent-exempt minimum")]
+    BelowRentExempt,
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+    #[msg("Inactivity period and delay must be positive")]
+    InvalidPeriod,
+    #[msg("Owner has been active within the inactivity period")]
+    OwnerStillActive,
+    #[msg("A recovery is already pending")]
+    RecoveryAlreadyStarted,
+    #[msg("No recovery is pending")]
+    RecoveryNotStarted,
+    #[msg("Recovery delay has not elapsed yet")]
+    RecoveryDelayNotElapsed,
+    #[msg("Stream schedule must satisfy start <= cliff <= end and start < end")]
+    InvalidSchedule,
+    #[msg("Nothing has vested since the last claim")]
+    NothingToClaim,
+    #[msg("Lamport instruction used on a token stream or the other way round")]
+    WrongStreamKind,
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { createAccount, createMint, getAccount, mintTo, transfer } from "@solana/spl-token";
 import { BlueshiftAnchorVault } from "../target/types/blueshift_anchor_vault";
 
 describe("blueshift_anchor_vault", () => {
@@ -7,10 +8,292 @@ describe("blueshift_anchor_vault", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
 
   const program = anchor.workspace.blueshiftAnchorVault as Program<BlueshiftAnchorVault>;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const wallet = provider.wallet as anchor.Wallet;
+  const vault = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+  const vaultState = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("vault_state"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+  // Always passed to deposit and withdraw, whether or not a recovery is configured.
+  const recovery = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("recovery"), wallet.publicKey.toBuffer()],
+    program.programId
+  )[0];
+  const amount = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL);
 
-  it("Is initialized!", async () => {
-    // Add your test here.
-    const tx = await program.methods.initialize().rpc();
-    console.log("Your transaction signature", tx);
+  it("Deposit and withdraw without accounting", async () => {
+    await program.methods
+      .deposit(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    await program.methods
+      .withdraw(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    if ((await provider.connection.getBalance(vault)) !== 0) throw new Error("vault not empty");
+  });
+
+  it("Deposit and withdraw with accounting and events", async () => {
+    await program.methods
+      .initState()
+      .accountsPartial({ signer: wallet.publicKey, vaultState })
+      .rpc();
+
+    const events: any[] = [];
+    const depositListener = program.addEventListener("depositEvent", (event) => events.push(event));
+    const withdrawListener = program.addEventListener("withdrawEvent", (event) => events.push(event));
+
+    await program.methods
+      .deposit(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState, recovery })
+      .rpc();
+    await program.methods
+      .withdraw(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState, recovery })
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    if (!state.totalDeposited.eq(amount)) throw new Error("total deposited mismatch");
+    if (!state.totalWithdrawn.eq(amount)) throw new Error("total withdrawn mismatch");
+    if (state.lastActivity.lten(0)) throw new Error("last activity not set");
+
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(depositListener);
+    await program.removeEventListener(withdrawListener);
+    if (events.length !== 2) throw new Error(`expected 2 events, got ${events.length}`);
+  });
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    try {
+      await promise;
+    } catch (e) {
+      if (e instanceof anchor.AnchorError && e.error.errorCode.code === code) return;
+      throw e;
+    }
+    throw new Error(`expected ${code}`);
+  };
+
+  it("Partial withdrawals keep the vault rent exempt", async () => {
+    await expectError(
+      program.methods
+        .withdraw(amount)
+        .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+        .rpc(),
+      "VaultNotFound"
+    );
+
+    await program.methods
+      .deposit(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    const half = amount.divn(2);
+    await program.methods
+      .withdraw(half)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    if ((await provider.connection.getBalance(vault)) !== amount.sub(half).toNumber()) throw new Error("balance mismatch");
+
+    // Leaving a single lamport behind would make the vault rent paying.
+    await expectError(
+      program.methods
+        .withdraw(amount.sub(half).subn(1))
+        .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+        .rpc(),
+      "BelowRentExempt"
+    );
+    await expectError(
+      program.methods
+        .withdraw(amount)
+        .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+        .rpc(),
+      "InsufficientFunds"
+    );
+    await program.methods
+      .withdraw(amount.sub(half))
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    if ((await provider.connection.getBalance(vault)) !== 0) throw new Error("vault not empty");
+  });
+
+  it("Guardian recovers an inactive vault to the beneficiary", async () => {
+    const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+    const guardian = anchor.web3.Keypair.generate();
+    const beneficiary = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(guardian.publicKey, anchor.web3.LAMPORTS_PER_SOL),
+      "confirmed"
+    );
+    const executeAccounts = {
+      guardian: guardian.publicKey,
+      owner: wallet.publicKey,
+      vault,
+      recovery,
+      beneficiary: beneficiary.publicKey,
+      vaultState,
+    };
+    const before = await program.account.vaultState.fetch(vaultState);
+
+    await program.methods
+      .deposit(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState, recovery })
+      .rpc();
+    // A zero delay would let the guardian start and execute in the same transaction.
+    await expectError(
+      program.methods
+        .setRecovery(guardian.publicKey, beneficiary.publicKey, new anchor.BN(2), new anchor.BN(0))
+        .accountsPartial({ signer: wallet.publicKey, recovery })
+        .rpc(),
+      "InvalidPeriod"
+    );
+    await program.methods
+      .setRecovery(guardian.publicKey, beneficiary.publicKey, new anchor.BN(2), new anchor.BN(2))
+      .accountsPartial({ signer: wallet.publicKey, recovery })
+      .rpc();
+
+    // The owner was just active.
+    await expectError(
+      program.methods.startRecovery().accountsPartial({ guardian: guardian.publicKey, recovery }).signers([guardian]).rpc(),
+      "OwnerStillActive"
+    );
+
+    // The owner cancels a started recovery.
+    await sleep(3000);
+    await program.methods.startRecovery().accountsPartial({ guardian: guardian.publicKey, recovery }).signers([guardian]).rpc();
+    await program.methods.cancelRecovery().accountsPartial({ signer: wallet.publicKey, recovery }).rpc();
+    await expectError(
+      program.methods.executeRecovery().accountsPartial(executeAccounts).signers([guardian]).rpc(),
+      "RecoveryNotStarted"
+    );
+
+    // A deposit counts as owner activity and cancels a started recovery as well.
+    await sleep(3000);
+    await program.methods.startRecovery().accountsPartial({ guardian: guardian.publicKey, recovery }).signers([guardian]).rpc();
+    await program.methods
+      .deposit(new anchor.BN(1))
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState, recovery })
+      .rpc();
+    await expectError(
+      program.methods.executeRecovery().accountsPartial(executeAccounts).signers([guardian]).rpc(),
+      "RecoveryNotStarted"
+    );
+
+    // Nobody cancels this time.
+    await sleep(3000);
+    await program.methods.startRecovery().accountsPartial({ guardian: guardian.publicKey, recovery }).signers([guardian]).rpc();
+    await expectError(
+      program.methods.executeRecovery().accountsPartial(executeAccounts).signers([guardian]).rpc(),
+      "RecoveryDelayNotElapsed"
+    );
+    await sleep(3000);
+    await program.methods.executeRecovery().accountsPartial(executeAccounts).signers([guardian]).rpc();
+    if ((await provider.connection.getBalance(vault)) !== 0) throw new Error("vault not empty");
+    if ((await provider.connection.getBalance(beneficiary.publicKey)) !== amount.toNumber() + 1) throw new Error("beneficiary not paid");
+    // The recovered amount is accounted as a withdrawal.
+    const after = await program.account.vaultState.fetch(vaultState);
+    if (!after.totalDeposited.sub(before.totalDeposited).eq(amount.addn(1))) throw new Error("total deposited mismatch");
+    if (!after.totalWithdrawn.sub(before.totalWithdrawn).eq(amount.addn(1))) throw new Error("total withdrawn mismatch");
+
+    await program.methods.removeRecovery().accountsPartial({ signer: wallet.publicKey, recovery }).rpc();
+  });
+
+  it("Stream lamports from the vault and cancel the rest", async () => {
+    const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+    const recipient = anchor.web3.Keypair.generate();
+    const id = new anchor.BN(1);
+    const stream = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stream"), wallet.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const total = amount.divn(2);
+    const withdrawnBefore = (await program.account.vaultState.fetch(vaultState)).totalWithdrawn;
+
+    await program.methods
+      .deposit(amount)
+      .accountsPartial({ signer: wallet.publicKey, vault, vaultState: null, recovery })
+      .rpc();
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createStream(id, recipient.publicKey, total, new anchor.BN(now), new anchor.BN(now + 20), new anchor.BN(now + 2))
+      .accountsPartial({ owner: wallet.publicKey, vault, stream, vaultState, recovery })
+      .rpc();
+    const withdrawn = (await program.account.vaultState.fetch(vaultState)).totalWithdrawn;
+    if (!withdrawn.eq(withdrawnBefore.add(total))) throw new Error("stream not counted as a withdrawal");
+
+    // Before the cliff nothing can be claimed.
+    await expectError(
+      program.methods.claim().accountsPartial({ stream, recipient: recipient.publicKey }).rpc(),
+      "NothingToClaim"
+    );
+
+    await sleep(4000);
+    await program.methods.claim().accountsPartial({ stream, recipient: recipient.publicKey }).rpc();
+    const claimed = (await program.account.stream.fetch(stream)).claimed;
+    if (claimed.lten(0) || claimed.gte(total)) throw new Error("unexpected claimed amount");
+    if ((await provider.connection.getBalance(recipient.publicKey)) !== claimed.toNumber()) throw new Error("recipient not paid");
+
+    await program.methods
+      .cancel()
+      .accountsPartial({ owner: wallet.publicKey, stream, recipient: recipient.publicKey })
+      .rpc();
+    if ((await provider.connection.getAccountInfo(stream)) !== null) throw new Error("stream not closed");
+    if ((await provider.connection.getBalance(recipient.publicKey)) >= total.toNumber()) throw new Error("recipient got unvested funds");
+  });
+
+  it("Stream tokens and cancel the rest", async () => {
+    const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+    const recipient = anchor.web3.Keypair.generate();
+    const id = new anchor.BN(2);
+    const stream = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stream"), wallet.publicKey.toBuffer(), id.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    )[0];
+    const streamTokenAccount = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("stream_tokens"), stream.toBuffer()],
+      program.programId
+    )[0];
+    const total = 1_000_000;
+
+    const mint = await createMint(provider.connection, wallet.payer, wallet.publicKey, null, 6);
+    const ownerTokenAccount = await createAccount(provider.connection, wallet.payer, mint, wallet.publicKey);
+    const recipientTokenAccount = await createAccount(provider.connection, wallet.payer, mint, recipient.publicKey);
+    await mintTo(provider.connection, wallet.payer, mint, ownerTokenAccount, wallet.payer, total);
+    const tokens = async (account: anchor.web3.PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createTokenStream(id, recipient.publicKey, new anchor.BN(total), new anchor.BN(now), new anchor.BN(now + 20), new anchor.BN(now + 2))
+      .accountsPartial({ owner: wallet.publicKey, mint, ownerTokenAccount, stream, streamTokenAccount })
+      .rpc();
+    if ((await tokens(streamTokenAccount)) !== total) throw new Error("stream not funded");
+
+    await expectError(
+      program.methods.claimTokens().accountsPartial({ stream, streamTokenAccount, recipientTokenAccount }).rpc(),
+      "NothingToClaim"
+    );
+
+    await sleep(4000);
+    await program.methods.claimTokens().accountsPartial({ stream, streamTokenAccount, recipientTokenAccount }).rpc();
+    const claimed = (await program.account.stream.fetch(stream)).claimed.toNumber();
+    if (claimed <= 0 || claimed >= total) throw new Error("unexpected claimed amount");
+    if ((await tokens(recipientTokenAccount)) !== claimed) throw new Error("recipient not paid");
+
+    // Tokens sent to the stream directly must not block the cancel.
+    await mintTo(provider.connection, wallet.payer, mint, ownerTokenAccount, wallet.payer, 1);
+    await transfer(provider.connection, wallet.payer, ownerTokenAccount, streamTokenAccount, wallet.payer, 1);
+
+    await program.methods
+      .cancelTokenStream()
+      .accountsPartial({ owner: wallet.publicKey, stream, streamTokenAccount, recipientTokenAccount, ownerTokenAccount })
+      .rpc();
+    if ((await provider.connection.getAccountInfo(stream)) !== null) throw new Error("stream not closed");
+    if ((await provider.connection.getAccountInfo(streamTokenAccount)) !== null) throw new Error("token account not closed");
+    const paid = await tokens(recipientTokenAccount);
+    if (paid < claimed || paid >= total) throw new Error("recipient got unvested tokens");
+    if ((await tokens(ownerTokenAccount)) !== total + 1 - paid) throw new Error("owner not refunded");
   });
 });
@@ -0,0 +1,33 @@
+use pinocchio::error::ProgramError;
+
+/// 自定义错误码，客户端可以通过 `Custom(n)` 解码
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum VaultError {
+    /// 签名者不是恢复配置或流记录的 owner 或 guardian，或 beneficiary、recipient 不匹配
+    Unauthorized = 0,
+    /// 不活跃期和延迟都必须大于 0
+    InvalidPeriod = 1,
+    /// owner 在不活跃期内有过操作
+    OwnerStillActive = 2,
+    /// 已经有一个进行中的恢复
+    RecoveryAlreadyStarted = 3,
+    /// 没有进行中的恢复
+    RecoveryNotStarted = 4,
+    /// 恢复的延迟还没有结束
+    RecoveryDelayNotElapsed = 5,
+    /// 流的时间表必须满足 start <= cliff <= end 且 start < end
+    InvalidSchedule = 6,
+    /// 上次领取之后没有新解锁的金额
+    NothingToClaim = 7,
+    /// 金库余额不足
+    InsufficientFunds = 8,
+    /// 金库剩余的余额低于免租金的最低值
+    BelowRentExempt = 9,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(e: VaultError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,54 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+
+use super::helpers::load_owner_recovery;
+use crate::RecoveryConfig;
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct CancelRecoveryAccounts<'a> {
+    pub recovery: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CancelRecoveryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, recovery] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_owner_recovery(owner, recovery)?;
+
+        Ok(Self { recovery })
+    }
+}
+
+/// owner 在恢复执行之前随时可以取消。Deposit 和 Withdraw 也有同样的效果
+pub struct CancelRecovery<'a> {
+    pub accounts: CancelRecoveryAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CancelRecovery<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = CancelRecoveryAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelRecovery<'a> {
+    pub const DISCRIMINATOR: u8 = 5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.recovery.try_borrow_mut_data()?;
+        RecoveryConfig::load_mut(&mut data)?.touch(now);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+
+use super::helpers::{load_stream, pay_out};
+use crate::{Stream, VaultError};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct CancelStreamAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub stream: &'a AccountInfo,
+    pub recipient: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CancelStreamAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, stream, recipient] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        load_stream(stream, recipient)?;
+
+        let mut data = stream.try_borrow_mut_data()?;
+        if Stream::load_mut(&mut data)?.owner.ne(owner.address()) {
+            return Err(VaultError::Unauthorized.into());
+        }
+
+        Ok(Self { owner, stream, recipient })
+    }
+}
+
+/// 停止流：recipient 拿到目前解锁的部分，剩下没解锁的 lamports 和租金退还给 owner
+pub struct CancelStream<'a> {
+    pub accounts: CancelStreamAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CancelStream<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = CancelStreamAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelStream<'a> {
+    pub const DISCRIMINATOR: u8 = 9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = {
+            let mut data = self.accounts.stream.try_borrow_mut_data()?;
+            Stream::load_mut(&mut data)?.claim(now)
+        };
+        if amount.ne(&0) {
+            pay_out(self.accounts.stream, self.accounts.recipient, amount)?;
+        }
+
+        pay_out(self.accounts.stream, self.accounts.owner, self.accounts.stream.lamports())?;
+        // 清空 lamports、数据和 owner，账户交还给 System Program
+        self.accounts.stream.close()?;
+
+        Ok(())
+    }
+}
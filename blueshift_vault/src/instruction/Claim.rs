@@ -0,0 +1,60 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+
+use super::helpers::{load_stream, pay_out};
+use crate::{Stream, VaultError};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct ClaimAccounts<'a> {
+    pub stream: &'a AccountInfo,
+    pub recipient: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ClaimAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [stream, recipient] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_stream(stream, recipient)?;
+
+        Ok(Self { stream, recipient })
+    }
+}
+
+/// 把已经解锁但还没领取的 lamports 转给 recipient，任何人都可以调用
+pub struct Claim<'a> {
+    pub accounts: ClaimAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for Claim<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = ClaimAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Claim<'a> {
+    pub const DISCRIMINATOR: u8 = 8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let amount = {
+            let mut data = self.accounts.stream.try_borrow_mut_data()?;
+            Stream::load_mut(&mut data)?.claim(now)
+        };
+        if amount.eq(&0) {
+            return Err(VaultError::NothingToClaim.into());
+        }
+
+        pay_out(self.accounts.stream, self.accounts.recipient, amount)
+    }
+}
@@ -0,0 +1,182 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::sysvars::{clock::Clock, rent::Rent, Sysvar};
+use pinocchio_system::instructions::Transfer;
+use core::mem::size_of;
+
+use super::helpers::{create_pda_account, touch_recovery};
+use crate::{Stream, VaultError, STREAM_SEED, VAULT_SEED};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct CreateStreamAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub stream: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
+    pub vault_bumps: [u8; 1],
+    pub stream_bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<(&'a [AccountInfo], u64)> for CreateStreamAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from((accounts, id): (&'a [AccountInfo], u64)) -> Result<Self, Self::Error> {
+        // recovery 和存取款一样必须传入
+        let [owner, vault, stream, system_program, recovery] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.address().ne(&pinocchio_system::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        if !vault.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let (vault_address, vault_bump) =
+            Address::find_program_address(&[VAULT_SEED, owner.address().as_ref()], &crate::ID);
+        if vault_address.ne(vault.address()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (stream_address, stream_bump) = Address::find_program_address(
+            &[STREAM_SEED, owner.address().as_ref(), &id.to_le_bytes()],
+            &crate::ID,
+        );
+        if stream_address.ne(stream.address()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            stream,
+            recovery,
+            vault_bumps: [vault_bump],
+            stream_bumps: [stream_bump],
+        })
+    }
+}
+
+/// 指令数据：id (u64) + recipient (32) + total (u64) + start (i64) + end (i64) + cliff (i64)
+pub struct CreateStreamInstructionData {
+    pub id: u64,
+    pub recipient: Address,
+    pub total: u64,
+    pub start: i64,
+    pub end: i64,
+    pub cliff: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for CreateStreamInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() * 2 + size_of::<Address>() + size_of::<i64>() * 3 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let id = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let recipient = Address::new_from_array(data[8..40].try_into().unwrap());
+        let total = u64::from_le_bytes(data[40..48].try_into().unwrap());
+        let start = i64::from_le_bytes(data[48..56].try_into().unwrap());
+        let end = i64::from_le_bytes(data[56..64].try_into().unwrap());
+        let cliff = i64::from_le_bytes(data[64..72].try_into().unwrap());
+
+        if total.eq(&0) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if !(start < end && start <= cliff && cliff <= end) {
+            return Err(VaultError::InvalidSchedule.into());
+        }
+
+        Ok(Self { id, recipient, total, start, end, cliff })
+    }
+}
+
+/// 从金库锁定 total lamports 到一个新的流。和取款一样会记为 owner 的活动。
+/// 这里只支持 lamports，SPL token 的流在 blueshift_anchor_vault 里
+pub struct CreateStream<'a> {
+    pub accounts: CreateStreamAccounts<'a>,
+    pub instruction_data: CreateStreamInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for CreateStream<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let instruction_data = CreateStreamInstructionData::try_from(data)?;
+        let accounts = CreateStreamAccounts::try_from((accounts, instruction_data.id))?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> CreateStream<'a> {
+    pub const DISCRIMINATOR: u8 = 7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let total = self.instruction_data.total;
+
+        // 金库要么被取空，要么剩下的余额仍然免租金，否则转账会被运行时拒绝
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(total)
+            .ok_or(VaultError::InsufficientFunds)?;
+        if remaining.ne(&0) && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(VaultError::BelowRentExempt.into());
+        }
+
+        let id = self.instruction_data.id.to_le_bytes();
+        let stream_seeds = [
+            Seed::from(STREAM_SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&id),
+            Seed::from(&self.accounts.stream_bumps),
+        ];
+        create_pda_account(self.accounts.owner, self.accounts.stream, Stream::LEN, &[Signer::from(&stream_seeds)])?;
+
+        {
+            let mut data = self.accounts.stream.try_borrow_mut_data()?;
+            Stream::load_mut(&mut data)?.set_inner(
+                *self.accounts.owner.address(),
+                self.instruction_data.recipient,
+                self.instruction_data.id,
+                total,
+                self.instruction_data.start,
+                self.instruction_data.end,
+                self.instruction_data.cliff,
+                self.accounts.stream_bumps,
+            );
+        }
+
+        let vault_seeds = [
+            Seed::from(VAULT_SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.vault_bumps),
+        ];
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.stream,
+            lamports: total,
+        }
+        .invoke_signed(&[Signer::from(&vault_seeds)])?;
+
+        touch_recovery(self.accounts.owner, self.accounts.recovery)?;
+
+        Ok(())
+    }
+}
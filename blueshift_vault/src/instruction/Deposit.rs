@@ -3,6 +3,8 @@ use pinocchio::error::ProgramError;
 use pinocchio_system::instructions::Transfer;
 use core::mem::size_of;
 
+use super::helpers::touch_recovery;
+
 // 类型别名
 type AccountInfo = AccountView;
 
@@ -10,13 +12,15 @@ type AccountInfo = AccountView;
 pub struct DepositAccounts<'a> {
     pub owner: &'a AccountInfo,
     pub vault: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [owner, vault, _] = accounts else {
+        // recovery 是 [b"recovery", owner]，即使没有配置恢复也必须传入
+        let [owner, vault, _, recovery] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
@@ -40,7 +44,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
      }
 
         // Return the accounts
-        Ok(Self { owner, vault })
+        Ok(Self { owner, vault, recovery })
     }
 }
 
@@ -97,6 +101,8 @@ impl<'a> Deposit<'a> {
         }
         .invoke()?;
 
+        touch_recovery(self.accounts.owner, self.accounts.recovery)?;
+
         Ok(())
     }
 }
\ No newline at end of file
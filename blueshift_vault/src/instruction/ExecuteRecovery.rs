@@ -0,0 +1,106 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+use pinocchio_system::instructions::Transfer;
+use solana_address::Address;
+
+use super::helpers::load_guardian_recovery;
+use crate::{RecoveryConfig, VaultError, VAULT_SEED};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct ExecuteRecoveryAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
+    pub beneficiary: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ExecuteRecoveryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [guardian, owner, vault, recovery, beneficiary, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_guardian_recovery(guardian, recovery)?;
+
+        {
+            let mut data = recovery.try_borrow_mut_data()?;
+            let config = RecoveryConfig::load_mut(&mut data)?;
+            if config.owner.ne(owner.address()) || config.beneficiary.ne(beneficiary.address()) {
+                return Err(VaultError::Unauthorized.into());
+            }
+        }
+
+        if vault.lamports().eq(&0) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let (vault_address, bump) =
+            Address::find_program_address(&[VAULT_SEED, owner.address().as_ref()], &crate::ID);
+        if vault_address.ne(vault.address()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self { owner, vault, recovery, beneficiary, bumps: [bump] })
+    }
+}
+
+/// 恢复发起 delay 秒之后，把金库的全部余额转给 beneficiary
+pub struct ExecuteRecovery<'a> {
+    pub accounts: ExecuteRecoveryAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ExecuteRecovery<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = ExecuteRecoveryAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ExecuteRecovery<'a> {
+    pub const DISCRIMINATOR: u8 = 6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        {
+            let mut data = self.accounts.recovery.try_borrow_mut_data()?;
+            let recovery = RecoveryConfig::load_mut(&mut data)?;
+            if recovery.recovery_started_at() == 0 {
+                return Err(VaultError::RecoveryNotStarted.into());
+            }
+            let executable_at = recovery
+                .recovery_started_at()
+                .checked_add(recovery.delay())
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            if now < executable_at {
+                return Err(VaultError::RecoveryDelayNotElapsed.into());
+            }
+            recovery.set_recovery_started_at(0);
+        }
+
+        let seeds = [
+            Seed::from(VAULT_SEED),
+            Seed::from(self.accounts.owner.address().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.beneficiary,
+            lamports: self.accounts.vault.lamports(),
+        }
+        .invoke_signed(&signers)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,61 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+
+use super::helpers::load_owner_recovery;
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct RemoveRecoveryAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for RemoveRecoveryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, recovery] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_owner_recovery(owner, recovery)?;
+
+        Ok(Self { owner, recovery })
+    }
+}
+
+/// 删除恢复配置，租金退还给 owner
+pub struct RemoveRecovery<'a> {
+    pub accounts: RemoveRecoveryAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for RemoveRecovery<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = RemoveRecoveryAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> RemoveRecovery<'a> {
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let owner = self.accounts.owner;
+        let recovery = self.accounts.recovery;
+
+        owner.set_lamports(
+            owner
+                .lamports()
+                .checked_add(recovery.lamports())
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        // 清空 lamports、数据和 owner，账户交还给 System Program
+        recovery.close()?;
+
+        Ok(())
+    }
+}
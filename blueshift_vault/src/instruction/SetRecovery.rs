@@ -0,0 +1,124 @@
+use pinocchio::{AccountView, Address, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::cpi::{Seed, Signer};
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+use core::mem::size_of;
+
+use super::helpers::create_pda_account;
+use crate::{RecoveryConfig, VaultError, RECOVERY_SEED};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct SetRecoveryAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetRecoveryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, recovery, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.address().ne(&pinocchio_system::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let (recovery_address, bump) =
+            Address::find_program_address(&[RECOVERY_SEED, owner.address().as_ref()], &crate::ID);
+        if recovery_address.ne(recovery.address()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self { owner, recovery, bumps: [bump] })
+    }
+}
+
+/// 指令数据：guardian (32) + beneficiary (32) + inactivity_period (i64) + delay (i64)
+pub struct SetRecoveryInstructionData {
+    pub guardian: Address,
+    pub beneficiary: Address,
+    pub inactivity_period: i64,
+    pub delay: i64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetRecoveryInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Address>() * 2 + size_of::<i64>() * 2 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let guardian = Address::new_from_array(data[0..32].try_into().unwrap());
+        let beneficiary = Address::new_from_array(data[32..64].try_into().unwrap());
+        let inactivity_period = i64::from_le_bytes(data[64..72].try_into().unwrap());
+        let delay = i64::from_le_bytes(data[72..80].try_into().unwrap());
+
+        // 没有延迟的话 guardian 可以在同一笔交易里发起并执行恢复，owner 来不及取消
+        if inactivity_period <= 0 || delay <= 0 {
+            return Err(VaultError::InvalidPeriod.into());
+        }
+
+        Ok(Self { guardian, beneficiary, inactivity_period, delay })
+    }
+}
+
+/// 创建或替换金库的恢复配置
+pub struct SetRecovery<'a> {
+    pub accounts: SetRecoveryAccounts<'a>,
+    pub instruction_data: SetRecoveryInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetRecovery<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetRecoveryAccounts::try_from(accounts)?;
+        let instruction_data = SetRecoveryInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetRecovery<'a> {
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub fn process(&mut self) -> ProgramResult {
+        if !self.accounts.recovery.owned_by(&crate::ID) {
+            let seeds = [
+                Seed::from(RECOVERY_SEED),
+                Seed::from(self.accounts.owner.address().as_ref()),
+                Seed::from(&self.accounts.bumps),
+            ];
+            let signers = [Signer::from(&seeds)];
+            create_pda_account(self.accounts.owner, self.accounts.recovery, RecoveryConfig::LEN, &signers)?;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.recovery.try_borrow_mut_data()?;
+        let recovery = RecoveryConfig::load_mut(&mut data)?;
+        recovery.set_inner(
+            *self.accounts.owner.address(),
+            self.instruction_data.guardian,
+            self.instruction_data.beneficiary,
+            self.instruction_data.inactivity_period,
+            self.instruction_data.delay,
+            self.accounts.bumps,
+        );
+        recovery.touch(now);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use pinocchio::{AccountView, ProgramResult};
+use pinocchio::error::ProgramError;
+use pinocchio::sysvars::{clock::Clock, Sysvar};
+
+use super::helpers::load_guardian_recovery;
+use crate::{RecoveryConfig, VaultError};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+pub struct StartRecoveryAccounts<'a> {
+    pub recovery: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for StartRecoveryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [guardian, recovery] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        load_guardian_recovery(guardian, recovery)?;
+
+        Ok(Self { recovery })
+    }
+}
+
+/// owner 不活跃足够久之后由 guardian 发起恢复
+pub struct StartRecovery<'a> {
+    pub accounts: StartRecoveryAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for StartRecovery<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = StartRecoveryAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> StartRecovery<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+        let mut data = self.accounts.recovery.try_borrow_mut_data()?;
+        let recovery = RecoveryConfig::load_mut(&mut data)?;
+
+        if recovery.recovery_started_at() != 0 {
+            return Err(VaultError::RecoveryAlreadyStarted.into());
+        }
+        let inactive_until = recovery
+            .last_activity()
+            .checked_add(recovery.inactivity_period())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if now < inactive_until {
+            return Err(VaultError::OwnerStillActive.into());
+        }
+        recovery.set_recovery_started_at(now);
+
+        Ok(())
+    }
+}
@@ -3,12 +3,15 @@ use pinocchio::error::ProgramError;
 use pinocchio::cpi::{Seed, Signer};
 use pinocchio_system::instructions::Transfer;
 use solana_address::Address;
+
+use super::helpers::touch_recovery;
 // 类型别名
 type AccountInfo = AccountView;
 
 pub struct WithdrawAccounts<'a> {
     pub owner: &'a AccountInfo,
     pub vault: &'a AccountInfo,
+    pub recovery: &'a AccountInfo,
     pub bumps: [u8; 1],
 }
 
@@ -16,7 +19,8 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [owner, vault, _] = accounts else {
+        // recovery 是 [b"recovery", owner]，即使没有配置恢复也必须传入
+        let [owner, vault, _, recovery] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
@@ -39,7 +43,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
         return Err(ProgramError::InvalidAccountData);
      }
 
-        Ok(Self { owner, vault, bumps: [bump] })
+        Ok(Self { owner, vault, recovery, bumps: [bump] })
     }
 }
 pub struct Withdraw<'a> {
@@ -76,6 +80,8 @@ impl<'a> Withdraw<'a> {
         }
         .invoke_signed(&signers)?;
 
+        touch_recovery(self.accounts.owner, self.accounts.recovery)?;
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -0,0 +1,136 @@
+use pinocchio::{
+    cpi::Signer,
+    error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+use crate::{RecoveryConfig, Stream, VaultError, RECOVERY_SEED, STREAM_SEED};
+
+// 类型别名
+type AccountInfo = AccountView;
+
+/// 创建本程序拥有的 PDA 账户。任何人都可以提前往 PDA 转入 lamports，这时账户仍归 System Program 所有，
+/// CreateAccount 会失败，所以改为补足租金后 Allocate + Assign，和 Anchor 的 init 相同
+pub fn create_pda_account(payer: &AccountInfo, account: &AccountInfo, space: usize, signers: &[Signer]) -> ProgramResult {
+    let rent_exemption = Rent::get()?.minimum_balance(space);
+    let lamports = account.lamports();
+    if lamports == 0 {
+        return CreateAccount {
+            from: payer,
+            to: account,
+            lamports: rent_exemption,
+            space: space as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(signers);
+    }
+
+    if !account.owned_by(&pinocchio_system::ID) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    if rent_exemption > lamports {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: rent_exemption - lamports,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account,
+        space: space as u64,
+    }
+    .invoke_signed(signers)?;
+    Assign {
+        account,
+        owner: &crate::ID,
+    }
+    .invoke_signed(signers)
+}
+
+/// 检查 recovery 是 owner 的 [b"recovery", owner] PDA。这个账户总是要传入，
+/// 这样 owner 的存取一定会被记为活动；没有配置恢复时什么也不做
+pub fn touch_recovery(owner: &AccountInfo, recovery: &AccountInfo) -> ProgramResult {
+    let (recovery_address, _) =
+        Address::find_program_address(&[RECOVERY_SEED, owner.address().as_ref()], &crate::ID);
+    if recovery_address.ne(recovery.address()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if !recovery.owned_by(&crate::ID) {
+        return Ok(());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut data = recovery.try_borrow_mut_data()?;
+    RecoveryConfig::load_mut(&mut data)?.touch(now);
+
+    Ok(())
+}
+
+/// owner 签名，且 recovery 是本程序为这个 owner 保存的恢复配置
+pub fn load_owner_recovery(owner: &AccountInfo, recovery: &AccountInfo) -> ProgramResult {
+    if !owner.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !recovery.owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let mut data = recovery.try_borrow_mut_data()?;
+    if RecoveryConfig::load_mut(&mut data)?.owner.ne(owner.address()) {
+        return Err(VaultError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// guardian 签名，且是 recovery 记录的 guardian
+pub fn load_guardian_recovery(guardian: &AccountInfo, recovery: &AccountInfo) -> ProgramResult {
+    if !guardian.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !recovery.owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let mut data = recovery.try_borrow_mut_data()?;
+    if RecoveryConfig::load_mut(&mut data)?.guardian.ne(guardian.address()) {
+        return Err(VaultError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// stream 是本程序保存的 lamports 流，recipient 是它记录的接收者
+pub fn load_stream(stream: &AccountInfo, recipient: &AccountInfo) -> ProgramResult {
+    if !stream.owned_by(&crate::ID) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let mut data = stream.try_borrow_mut_data()?;
+    let config = Stream::load_mut(&mut data)?;
+    let stream_address = Address::create_program_address(
+        &[STREAM_SEED, config.owner.as_ref(), &config.id, &config.bump],
+        &crate::ID,
+    )
+    .map_err(|_| ProgramError::InvalidSeeds)?;
+    if stream_address.ne(stream.address()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if config.recipient.ne(recipient.address()) {
+        return Err(VaultError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// 从本程序拥有的 from 直接转出 lamports，不需要经过 System Program
+pub fn pay_out(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    from.set_lamports(
+        from.lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?,
+    );
+    to.set_lamports(
+        to.lamports()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?,
+    );
+    Ok(())
+}
@@ -1,2 +1,12 @@
+pub mod helpers;
+
 pub mod Deposit;
-pub mod Withdraw;
\ No newline at end of file
+pub mod Withdraw;
+pub mod SetRecovery;
+pub mod RemoveRecovery;
+pub mod StartRecovery;
+pub mod CancelRecovery;
+pub mod ExecuteRecovery;
+pub mod CreateStream;
+pub mod Claim;
+pub mod CancelStream;
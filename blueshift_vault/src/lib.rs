@@ -11,6 +11,14 @@ type Pubkey = Address;
 mod instruction;
 use instruction::Deposit;
 use instruction::Withdraw;
+use instruction::{CancelRecovery, ExecuteRecovery, RemoveRecovery, SetRecovery, StartRecovery};
+use instruction::{CancelStream, Claim, CreateStream};
+
+pub mod error;
+pub use error::*;
+
+pub mod state;
+pub use state::*;
 
 entrypoint!(process_instruction);
 nostd_panic_handler!();
@@ -37,6 +45,30 @@ fn process_instruction(
             let mut withdraw = Withdraw::Withdraw::try_from(accounts)?;
             withdraw.process()
         },
+        Some((discriminator, data)) if *discriminator == SetRecovery::SetRecovery::DISCRIMINATOR => {
+            SetRecovery::SetRecovery::try_from((data, accounts))?.process()
+        },
+        Some((discriminator, _)) if *discriminator == RemoveRecovery::RemoveRecovery::DISCRIMINATOR => {
+            RemoveRecovery::RemoveRecovery::try_from(accounts)?.process()
+        },
+        Some((discriminator, _)) if *discriminator == StartRecovery::StartRecovery::DISCRIMINATOR => {
+            StartRecovery::StartRecovery::try_from(accounts)?.process()
+        },
+        Some((discriminator, _)) if *discriminator == CancelRecovery::CancelRecovery::DISCRIMINATOR => {
+            CancelRecovery::CancelRecovery::try_from(accounts)?.process()
+        },
+        Some((discriminator, _)) if *discriminator == ExecuteRecovery::ExecuteRecovery::DISCRIMINATOR => {
+            ExecuteRecovery::ExecuteRecovery::try_from(accounts)?.process()
+        },
+        Some((discriminator, data)) if *discriminator == CreateStream::CreateStream::DISCRIMINATOR => {
+            CreateStream::CreateStream::try_from((data, accounts))?.process()
+        },
+        Some((discriminator, _)) if *discriminator == Claim::Claim::DISCRIMINATOR => {
+            Claim::Claim::try_from(accounts)?.process()
+        },
+        Some((discriminator, _)) if *discriminator == CancelStream::CancelStream::DISCRIMINATOR => {
+            CancelStream::CancelStream::try_from(accounts)?.process()
+        },
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
\ No newline at end of file
@@ -0,0 +1,184 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+/// 金库 PDA 的种子前缀，完整种子为 [b"vault", owner]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// 恢复配置 PDA 的种子前缀，完整种子为 [b"recovery", owner]
+pub const RECOVERY_SEED: &[u8] = b"recovery";
+
+/// 金库的紧急恢复配置：owner 连续 inactivity_period 秒没有操作时，guardian 可以发起恢复，
+/// 再过 delay 秒后把整个金库转给 beneficiary。期间 owner 的任何操作都会取消恢复
+#[repr(C)]
+pub struct RecoveryConfig {
+    pub owner: Address,                // 金库的 owner
+    pub guardian: Address,             // 可以发起和执行恢复
+    pub beneficiary: Address,          // 接收金库余额
+    pub inactivity_period: [u8; 8],    // i64，小端序
+    pub delay: [u8; 8],                // i64，小端序
+    pub last_activity: [u8; 8],        // owner 最后一次操作的 Unix 时间戳，i64，小端序
+    pub recovery_started_at: [u8; 8],  // 没有进行中的恢复时为 0，i64，小端序
+    pub bump: [u8; 1],                 // PDA bump seed
+}
+
+impl RecoveryConfig {
+    pub const LEN: usize = size_of::<Address>() * 3 + size_of::<[u8; 8]>() * 4 + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != RecoveryConfig::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn inactivity_period(&self) -> i64 {
+        i64::from_le_bytes(self.inactivity_period)
+    }
+
+    #[inline(always)]
+    pub fn delay(&self) -> i64 {
+        i64::from_le_bytes(self.delay)
+    }
+
+    #[inline(always)]
+    pub fn last_activity(&self) -> i64 {
+        i64::from_le_bytes(self.last_activity)
+    }
+
+    #[inline(always)]
+    pub fn recovery_started_at(&self) -> i64 {
+        i64::from_le_bytes(self.recovery_started_at)
+    }
+
+    #[inline(always)]
+    pub fn set_recovery_started_at(&mut self, now: i64) {
+        self.recovery_started_at = now.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        owner: Address,
+        guardian: Address,
+        beneficiary: Address,
+        inactivity_period: i64,
+        delay: i64,
+        bump: [u8; 1],
+    ) {
+        self.owner = owner;
+        self.guardian = guardian;
+        self.beneficiary = beneficiary;
+        self.inactivity_period = inactivity_period.to_le_bytes();
+        self.delay = delay.to_le_bytes();
+        self.bump = bump;
+    }
+
+    /// owner 的操作：重新开始计算不活跃期，并取消进行中的恢复
+    #[inline(always)]
+    pub fn touch(&mut self, now: i64) {
+        self.last_activity = now.to_le_bytes();
+        self.recovery_started_at = 0i64.to_le_bytes();
+    }
+}
+
+/// 流 PDA 的种子前缀，完整种子为 [b"stream", owner, id (u64，小端序)]
+pub const STREAM_SEED: &[u8] = b"stream";
+
+/// 从金库锁定 total lamports，在 start 到 end 之间线性解锁给 recipient，cliff 之前不能领取。
+/// 资金就存放在这个账户里。SPL token 的流只有 blueshift_anchor_vault 支持
+#[repr(C)]
+pub struct Stream {
+    pub owner: Address,      // 金库的 owner，可以取消流
+    pub recipient: Address,  // 接收解锁的 lamports
+    pub id: [u8; 8],         // u64，小端序，同一个 owner 可以有多个流
+    pub total: [u8; 8],      // u64，小端序
+    pub claimed: [u8; 8],    // u64，小端序
+    pub start: [u8; 8],      // i64，小端序
+    pub end: [u8; 8],        // i64，小端序
+    pub cliff: [u8; 8],      // i64，小端序
+    pub bump: [u8; 1],       // PDA bump seed
+}
+
+impl Stream {
+    pub const LEN: usize = size_of::<Address>() * 2 + size_of::<[u8; 8]>() * 6 + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Stream::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn total(&self) -> u64 {
+        u64::from_le_bytes(self.total)
+    }
+
+    #[inline(always)]
+    pub fn claimed(&self) -> u64 {
+        u64::from_le_bytes(self.claimed)
+    }
+
+    #[inline(always)]
+    pub fn start(&self) -> i64 {
+        i64::from_le_bytes(self.start)
+    }
+
+    #[inline(always)]
+    pub fn end(&self) -> i64 {
+        i64::from_le_bytes(self.end)
+    }
+
+    #[inline(always)]
+    pub fn cliff(&self) -> i64 {
+        i64::from_le_bytes(self.cliff)
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(
+        &mut self,
+        owner: Address,
+        recipient: Address,
+        id: u64,
+        total: u64,
+        start: i64,
+        end: i64,
+        cliff: i64,
+        bump: [u8; 1],
+    ) {
+        self.owner = owner;
+        self.recipient = recipient;
+        self.id = id.to_le_bytes();
+        self.total = total.to_le_bytes();
+        self.claimed = 0u64.to_le_bytes();
+        self.start = start.to_le_bytes();
+        self.end = end.to_le_bytes();
+        self.cliff = cliff.to_le_bytes();
+        self.bump = bump;
+    }
+
+    /// now 时刻已经解锁的金额
+    pub fn vested(&self, now: i64) -> u64 {
+        if now < self.cliff() {
+            return 0;
+        }
+        if now >= self.end() {
+            return self.total();
+        }
+        let elapsed = (now - self.start()) as u128;
+        let duration = (self.end() - self.start()) as u128;
+        (self.total() as u128 * elapsed / duration) as u64
+    }
+
+    /// 把目前解锁的金额都记为已领取，返回这次新增的可领取金额。时钟回拨时返回 0，不会下溢
+    pub fn claim(&mut self, now: i64) -> u64 {
+        let claimed = self.claimed();
+        let amount = self.vested(now).saturating_sub(claimed);
+        self.claimed = claimed.saturating_add(amount).to_le_bytes();
+        amount
+    }
+}
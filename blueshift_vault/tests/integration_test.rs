@@ -0,0 +1,484 @@
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+// 程序通过 target/deploy/blueshift_vault.so 加载。
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use std::str::FromStr;
+
+const DEPOSIT: u8 = 0;
+const WITHDRAW: u8 = 1;
+const SET_RECOVERY: u8 = 2;
+const REMOVE_RECOVERY: u8 = 3;
+const START_RECOVERY: u8 = 4;
+const CANCEL_RECOVERY: u8 = 5;
+const EXECUTE_RECOVERY: u8 = 6;
+const CREATE_STREAM: u8 = 7;
+const CLAIM: u8 = 8;
+const CANCEL_STREAM: u8 = 9;
+const USER_LAMPORTS: u64 = 10_000_000_000;
+const AMOUNT: u64 = 1_000_000_000;
+const INACTIVITY_PERIOD: i64 = 100;
+const DELAY: i64 = 50;
+
+// owner (32) + guardian (32) + beneficiary (32) + inactivity_period (8) + delay (8) + last_activity (8)
+// + recovery_started_at (8) + bump (1)
+const RECOVERY_LEN: usize = 129;
+const RECOVERY_STARTED_AT: usize = 120;
+
+// VaultError
+const UNAUTHORIZED: u32 = 0;
+const INVALID_PERIOD: u32 = 1;
+const OWNER_STILL_ACTIVE: u32 = 2;
+const RECOVERY_NOT_STARTED: u32 = 4;
+const RECOVERY_DELAY_NOT_ELAPSED: u32 = 5;
+const NOTHING_TO_CLAIM: u32 = 7;
+const INSUFFICIENT_FUNDS: u32 = 8;
+const BELOW_RENT_EXEMPT: u32 = 9;
+const STREAM_ID: u64 = 7;
+const STREAM_DURATION: i64 = 100;
+const STREAM_CLIFF: i64 = 10;
+
+fn program_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn vault_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id()).0
+}
+
+fn recovery_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"recovery", owner.as_ref()], &program_id()).0
+}
+
+fn stream_pda(owner: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"stream", owner.as_ref(), &id.to_le_bytes()], &program_id()).0
+}
+
+fn system_account() -> Account {
+    Account {
+        lamports: USER_LAMPORTS,
+        data: vec![],
+        owner: system_program::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn program_test(users: &[&Pubkey]) -> ProgramTest {
+    // processor 传 None：加载 target/deploy 下的 SBF 程序
+    let mut program_test = ProgramTest::new("blueshift_vault", program_id(), None);
+    program_test.prefer_bpf(true);
+    for user in users {
+        program_test.add_account(**user, system_account());
+    }
+    program_test
+}
+
+fn deposit_instruction(owner: &Pubkey, amount: u64) -> Instruction {
+    let mut data = vec![DEPOSIT];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(vault_pda(owner), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(recovery_pda(owner), false),
+        ],
+        data,
+    }
+}
+
+fn withdraw_instruction(owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(vault_pda(owner), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(recovery_pda(owner), false),
+        ],
+        data: vec![WITHDRAW],
+    }
+}
+
+fn set_recovery_instruction(owner: &Pubkey, guardian: &Pubkey, beneficiary: &Pubkey, delay: i64) -> Instruction {
+    let mut data = vec![SET_RECOVERY];
+    data.extend_from_slice(guardian.as_ref());
+    data.extend_from_slice(beneficiary.as_ref());
+    data.extend_from_slice(&INACTIVITY_PERIOD.to_le_bytes());
+    data.extend_from_slice(&delay.to_le_bytes());
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(recovery_pda(owner), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    }
+}
+
+fn recovery_instruction(signer: &Pubkey, owner: &Pubkey, discriminator: u8) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![AccountMeta::new(*signer, true), AccountMeta::new(recovery_pda(owner), false)],
+        data: vec![discriminator],
+    }
+}
+
+fn execute_recovery_instruction(guardian: &Pubkey, owner: &Pubkey, beneficiary: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*guardian, true),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(vault_pda(owner), false),
+            AccountMeta::new(recovery_pda(owner), false),
+            AccountMeta::new(*beneficiary, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![EXECUTE_RECOVERY],
+    }
+}
+
+fn create_stream_instruction(owner: &Pubkey, recipient: &Pubkey, total: u64, start: i64) -> Instruction {
+    let mut data = vec![CREATE_STREAM];
+    data.extend_from_slice(&STREAM_ID.to_le_bytes());
+    data.extend_from_slice(recipient.as_ref());
+    data.extend_from_slice(&total.to_le_bytes());
+    data.extend_from_slice(&start.to_le_bytes());
+    data.extend_from_slice(&(start + STREAM_DURATION).to_le_bytes());
+    data.extend_from_slice(&(start + STREAM_CLIFF).to_le_bytes());
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(vault_pda(owner), false),
+            AccountMeta::new(stream_pda(owner, STREAM_ID), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(recovery_pda(owner), false),
+        ],
+        data,
+    }
+}
+
+fn claim_instruction(owner: &Pubkey, recipient: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(stream_pda(owner, STREAM_ID), false),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: vec![CLAIM],
+    }
+}
+
+fn cancel_stream_instruction(owner: &Pubkey, recipient: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*owner, true),
+            AccountMeta::new(stream_pda(owner, STREAM_ID), false),
+            AccountMeta::new(*recipient, false),
+        ],
+        data: vec![CANCEL_STREAM],
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    // 同一条指令可能发送多次，每次换一个 blockhash，避免被当作重复交易
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+// 把链上时间往后拨 seconds 秒
+async fn warp(context: &mut ProgramTestContext, seconds: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp += seconds;
+    context.set_sysvar(&clock);
+}
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp
+}
+
+async fn balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context.banks_client.get_balance(*address).await.unwrap()
+}
+
+async fn recovery_started_at(context: &mut ProgramTestContext, owner: &Pubkey) -> i64 {
+    let account = context.banks_client.get_account(recovery_pda(owner)).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), RECOVERY_LEN);
+    i64::from_le_bytes(account.data[RECOVERY_STARTED_AT..RECOVERY_STARTED_AT + 8].try_into().unwrap())
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, expected)
+    );
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw_without_recovery() {
+    let owner = Keypair::new();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, &vault_pda(&owner.pubkey())).await, AMOUNT);
+    process(&mut context, withdraw_instruction(&owner.pubkey()), &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, &vault_pda(&owner.pubkey())).await, 0);
+}
+
+#[tokio::test]
+async fn test_deposit_with_wrong_recovery_is_rejected() {
+    let owner = Keypair::new();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    let mut deposit = deposit_instruction(&owner.pubkey(), AMOUNT);
+    deposit.accounts[3] = AccountMeta::new(recovery_pda(&Keypair::new().pubkey()), false);
+    let result = process(&mut context, deposit, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
+
+#[tokio::test]
+async fn test_set_recovery_without_delay_is_rejected() {
+    let owner = Keypair::new();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    let guardian = Keypair::new().pubkey();
+    let beneficiary = Keypair::new().pubkey();
+    let result = process(
+        &mut context,
+        set_recovery_instruction(&owner.pubkey(), &guardian, &beneficiary, 0),
+        &[&owner],
+    )
+    .await;
+
+    assert_instruction_error(result, InstructionError::Custom(INVALID_PERIOD));
+}
+
+#[tokio::test]
+async fn test_set_recovery_on_prefunded_pda() {
+    let owner = Keypair::new();
+    let mut program_test = program_test(&[&owner.pubkey()]);
+    // 任何人都可以提前往 PDA 转账，这不能阻止 owner 配置恢复
+    program_test.add_account(
+        recovery_pda(&owner.pubkey()),
+        Account { lamports: 1, ..system_account() },
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let guardian = Keypair::new().pubkey();
+    let beneficiary = Keypair::new().pubkey();
+    process(
+        &mut context,
+        set_recovery_instruction(&owner.pubkey(), &guardian, &beneficiary, DELAY),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(recovery_started_at(&mut context, &owner.pubkey()).await, 0);
+}
+
+#[tokio::test]
+async fn test_guardian_recovers_inactive_vault() {
+    let owner = Keypair::new();
+    let guardian = Keypair::new();
+    let beneficiary = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey(), &guardian.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let set_recovery = set_recovery_instruction(&owner.pubkey(), &guardian.pubkey(), &beneficiary, DELAY);
+    process(&mut context, set_recovery, &[&owner]).await.unwrap();
+
+    // owner 刚刚操作过
+    let start = recovery_instruction(&guardian.pubkey(), &owner.pubkey(), START_RECOVERY);
+    let result = process(&mut context, start.clone(), &[&guardian]).await;
+    assert_instruction_error(result, InstructionError::Custom(OWNER_STILL_ACTIVE));
+
+    warp(&mut context, INACTIVITY_PERIOD).await;
+    process(&mut context, start, &[&guardian]).await.unwrap();
+    let execute = execute_recovery_instruction(&guardian.pubkey(), &owner.pubkey(), &beneficiary);
+    let result = process(&mut context, execute.clone(), &[&guardian]).await;
+    assert_instruction_error(result, InstructionError::Custom(RECOVERY_DELAY_NOT_ELAPSED));
+
+    warp(&mut context, DELAY).await;
+    process(&mut context, execute, &[&guardian]).await.unwrap();
+
+    assert_eq!(balance(&mut context, &vault_pda(&owner.pubkey())).await, 0);
+    assert_eq!(balance(&mut context, &beneficiary).await, AMOUNT);
+    assert_eq!(recovery_started_at(&mut context, &owner.pubkey()).await, 0);
+}
+
+#[tokio::test]
+async fn test_owner_cancels_recovery() {
+    let owner = Keypair::new();
+    let guardian = Keypair::new();
+    let beneficiary = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey(), &guardian.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let set_recovery = set_recovery_instruction(&owner.pubkey(), &guardian.pubkey(), &beneficiary, DELAY);
+    process(&mut context, set_recovery, &[&owner]).await.unwrap();
+
+    warp(&mut context, INACTIVITY_PERIOD).await;
+    let start = recovery_instruction(&guardian.pubkey(), &owner.pubkey(), START_RECOVERY);
+    process(&mut context, start.clone(), &[&guardian]).await.unwrap();
+    let cancel = recovery_instruction(&owner.pubkey(), &owner.pubkey(), CANCEL_RECOVERY);
+    process(&mut context, cancel, &[&owner]).await.unwrap();
+
+    warp(&mut context, DELAY).await;
+    let execute = execute_recovery_instruction(&guardian.pubkey(), &owner.pubkey(), &beneficiary);
+    let result = process(&mut context, execute, &[&guardian]).await;
+    assert_instruction_error(result, InstructionError::Custom(RECOVERY_NOT_STARTED));
+
+    // 取款同样算作 owner 的操作
+    warp(&mut context, INACTIVITY_PERIOD).await;
+    process(&mut context, start, &[&guardian]).await.unwrap();
+    assert_ne!(recovery_started_at(&mut context, &owner.pubkey()).await, 0);
+    process(&mut context, withdraw_instruction(&owner.pubkey()), &[&owner]).await.unwrap();
+    assert_eq!(recovery_started_at(&mut context, &owner.pubkey()).await, 0);
+
+    let remove = recovery_instruction(&owner.pubkey(), &owner.pubkey(), REMOVE_RECOVERY);
+    process(&mut context, remove, &[&owner]).await.unwrap();
+    assert!(context.banks_client.get_account(recovery_pda(&owner.pubkey())).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_only_guardian_can_start_recovery() {
+    let owner = Keypair::new();
+    let guardian = Keypair::new().pubkey();
+    let beneficiary = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    let set_recovery = set_recovery_instruction(&owner.pubkey(), &guardian, &beneficiary, DELAY);
+    process(&mut context, set_recovery, &[&owner]).await.unwrap();
+    warp(&mut context, INACTIVITY_PERIOD).await;
+
+    // owner 自己也不能代替 guardian
+    let start = recovery_instruction(&owner.pubkey(), &owner.pubkey(), START_RECOVERY);
+    let result = process(&mut context, start, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn test_stream_vests_linearly() {
+    let owner = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let start = now(&mut context).await;
+    let create = create_stream_instruction(&owner.pubkey(), &recipient, AMOUNT, start);
+    process(&mut context, create, &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, &vault_pda(&owner.pubkey())).await, 0);
+
+    // cliff 之前什么都领不到
+    let result = process(&mut context, claim_instruction(&owner.pubkey(), &recipient), &[]).await;
+    assert_instruction_error(result, InstructionError::Custom(NOTHING_TO_CLAIM));
+
+    warp(&mut context, STREAM_DURATION / 2).await;
+    process(&mut context, claim_instruction(&owner.pubkey(), &recipient), &[]).await.unwrap();
+    assert_eq!(balance(&mut context, &recipient).await, AMOUNT / 2);
+
+    warp(&mut context, STREAM_DURATION).await;
+    process(&mut context, claim_instruction(&owner.pubkey(), &recipient), &[]).await.unwrap();
+    assert_eq!(balance(&mut context, &recipient).await, AMOUNT);
+}
+
+#[tokio::test]
+async fn test_cancel_stream_splits_funds() {
+    let owner = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let owner_balance = balance(&mut context, &owner.pubkey()).await;
+    let start = now(&mut context).await;
+    let create = create_stream_instruction(&owner.pubkey(), &recipient, AMOUNT / 2, start);
+    process(&mut context, create, &[&owner]).await.unwrap();
+    assert_eq!(balance(&mut context, &vault_pda(&owner.pubkey())).await, AMOUNT / 2);
+
+    // 只有 owner 可以取消
+    let other = Keypair::new();
+    let mut cancel = cancel_stream_instruction(&owner.pubkey(), &recipient);
+    cancel.accounts[0] = AccountMeta::new(other.pubkey(), true);
+    let result = process(&mut context, cancel, &[&other]).await;
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+
+    warp(&mut context, STREAM_DURATION / 2).await;
+    let cancel = cancel_stream_instruction(&owner.pubkey(), &recipient);
+    process(&mut context, cancel, &[&owner]).await.unwrap();
+
+    // recipient 拿到解锁的一半，owner 拿回另一半和租金
+    assert_eq!(balance(&mut context, &recipient).await, AMOUNT / 4);
+    assert_eq!(balance(&mut context, &owner.pubkey()).await, owner_balance + AMOUNT / 4);
+    assert!(context.banks_client.get_account(stream_pda(&owner.pubkey(), STREAM_ID)).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_claim_to_wrong_recipient_is_rejected() {
+    let owner = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let start = now(&mut context).await;
+    let create = create_stream_instruction(&owner.pubkey(), &recipient, AMOUNT, start);
+    process(&mut context, create, &[&owner]).await.unwrap();
+
+    warp(&mut context, STREAM_DURATION).await;
+    let result = process(&mut context, claim_instruction(&owner.pubkey(), &owner.pubkey()), &[]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn test_create_stream_checks_vault_balance() {
+    let owner = Keypair::new();
+    let recipient = Keypair::new().pubkey();
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    process(&mut context, deposit_instruction(&owner.pubkey(), AMOUNT), &[&owner]).await.unwrap();
+    let start = now(&mut context).await;
+
+    let create = create_stream_instruction(&owner.pubkey(), &recipient, AMOUNT + 1, start);
+    let result = process(&mut context, create, &[&owner]).await;
+    assert_instruction_error(result, InstructionError::Custom(INSUFFICIENT_FUNDS));
+
+    // 金库只剩 1 lamport，低于免租金的最低值
+    let create = create_stream_instruction(&owner.pubkey(), &recipient, AMOUNT - 1, start);
+    let result = process(&mut context, create, &[&owner]).await;
+    assert_instruction_error(result, InstructionError::Custom(BELOW_RENT_EXEMPT));
+}
@@ -0,0 +1,21 @@
+use pinocchio::error::ProgramError;
+
+/// 计数器程序的自定义错误码，客户端可以通过 `Custom(n)` 解码
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CounterError {
+    /// 签名者不是计数器记录的 authority
+    Unauthorized = 0,
+    /// 递增溢出 u64
+    Overflow = 1,
+    /// 递减低于 0
+    Underflow = 2,
+    /// 计数器账户不是 [b"counter", user] PDA
+    InvalidCounterAddress = 3,
+}
+
+impl From<CounterError> for ProgramError {
+    fn from(e: CounterError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,57 @@
+use core::mem::size_of;
+use pinocchio::{AccountView, error::ProgramError};
+
+use crate::{Counter, CounterError};
+
+/// 修改已有计数器的指令共用的账户：[authority, counter]
+pub struct CounterAccounts<'a> {
+    pub authority: &'a AccountView,
+    pub counter: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for CounterAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [authority, counter, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // 只有本程序创建的账户才可能是计数器
+        if !counter.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !counter.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 签名者必须是计数器记录的 authority
+        let data = counter.try_borrow_data()?;
+        let state = Counter::load(&data)?;
+        if state.authority.ne(authority.address()) {
+            return Err(CounterError::Unauthorized.into());
+        }
+
+        Ok(Self { authority, counter })
+    }
+}
+
+/// 解析 8 字节小端序的 u64 参数
+pub fn parse_amount(data: &[u8]) -> Result<u64, ProgramError> {
+    if data.len() != size_of::<u64>() {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+    if amount == 0 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    Ok(amount)
+}
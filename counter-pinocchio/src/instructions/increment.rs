@@ -0,0 +1,37 @@
+use pinocchio::{AccountView, error::ProgramError, ProgramResult};
+
+use crate::helpers::{parse_amount, CounterAccounts};
+use crate::{Counter, CounterError};
+
+pub struct Increment<'a> {
+    pub accounts: CounterAccounts<'a>,
+    pub by: u64,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Increment<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = CounterAccounts::try_from(accounts)?;
+        let by = parse_amount(data)?;
+
+        Ok(Self { accounts, by })
+    }
+}
+
+impl<'a> Increment<'a> {
+    pub const DISCRIMINATOR: u8 = 1;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.counter.try_borrow_mut_data()?;
+        let counter = Counter::load_mut(&mut data)?;
+
+        let count = counter
+            .count()
+            .checked_add(self.by)
+            .ok_or(CounterError::Overflow)?;
+        counter.set_count(count);
+
+        Ok(())
+    }
+}
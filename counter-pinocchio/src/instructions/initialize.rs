@@ -0,0 +1,115 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+use crate::{Counter, CounterError, COUNTER_SEED};
+
+pub struct InitializeAccounts<'a> {
+    pub user: &'a AccountView,
+    pub counter: &'a AccountView,
+    pub bump: u8,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitializeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [user, counter, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        if system_program.address().ne(&pinocchio_system::ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        // 每个用户一个计数器：[b"counter", user]
+        let (counter_address, bump) =
+            Address::find_program_address(&[COUNTER_SEED, user.address().as_ref()], &crate::ID);
+        if counter_address.ne(counter.address()) {
+            return Err(CounterError::InvalidCounterAddress.into());
+        }
+
+        // 任何人都可以提前往 PDA 转入 lamports，所以只有不再归 System Program 所有才算初始化过
+        if counter.lamports() != 0 && !counter.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+
+        Ok(Self { user, counter, bump })
+    }
+}
+
+pub struct Initialize<'a> {
+    pub accounts: InitializeAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Initialize<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = InitializeAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Initialize<'a> {
+    pub const DISCRIMINATOR: u8 = 0;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let bump = [self.accounts.bump];
+        let seeds = [
+            Seed::from(COUNTER_SEED),
+            Seed::from(self.accounts.user.address().as_ref()),
+            Seed::from(&bump),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        let rent_exemption = Rent::get()?.minimum_balance(Counter::LEN);
+        let lamports = self.accounts.counter.lamports();
+        if lamports == 0 {
+            CreateAccount {
+                from: self.accounts.user,
+                to: self.accounts.counter,
+                lamports: rent_exemption,
+                space: Counter::LEN as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&signers)?;
+        } else {
+            // 已经有 lamports 时 CreateAccount 会失败：补足租金后 Allocate + Assign，和 Anchor 的 init 相同
+            if rent_exemption > lamports {
+                Transfer {
+                    from: self.accounts.user,
+                    to: self.accounts.counter,
+                    lamports: rent_exemption - lamports,
+                }
+                .invoke()?;
+            }
+            Allocate {
+                account: self.accounts.counter,
+                space: Counter::LEN as u64,
+            }
+            .invoke_signed(&signers)?;
+            Assign {
+                account: self.accounts.counter,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // 创建者同时是初始 authority，计数从 0 开始
+        let mut data = self.accounts.counter.try_borrow_mut_data()?;
+        let counter = Counter::load_mut(&mut data)?;
+        counter.set_inner(*self.accounts.user.address(), *self.accounts.user.address(), 0, bump);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,16 @@
+pub mod helpers;
+
+pub mod initialize;
+pub use initialize::*;
+
+pub mod increment;
+pub use increment::*;
+
+pub mod decrement;
+pub use decrement::*;
+
+pub mod reset;
+pub use reset::*;
+
+pub mod set_authority;
+pub use set_authority::*;
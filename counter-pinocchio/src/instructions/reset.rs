@@ -0,0 +1,29 @@
+use pinocchio::{AccountView, error::ProgramError, ProgramResult};
+
+use crate::helpers::CounterAccounts;
+use crate::Counter;
+
+pub struct Reset<'a> {
+    pub accounts: CounterAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Reset<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = CounterAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Reset<'a> {
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.counter.try_borrow_mut_data()?;
+        Counter::load_mut(&mut data)?.set_count(0);
+
+        Ok(())
+    }
+}
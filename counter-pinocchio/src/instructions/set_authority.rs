@@ -0,0 +1,53 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, AccountView, Address, ProgramResult};
+
+use crate::helpers::CounterAccounts;
+use crate::Counter;
+
+pub struct SetAuthorityInstructionData {
+    pub new_authority: Address,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetAuthorityInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Address>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            new_authority: data.try_into().unwrap(),
+        })
+    }
+}
+
+pub struct SetAuthority<'a> {
+    pub accounts: CounterAccounts<'a>,
+    pub instruction_data: SetAuthorityInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetAuthority<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = CounterAccounts::try_from(accounts)?;
+        let instruction_data = SetAuthorityInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetAuthority<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.counter.try_borrow_mut_data()?;
+        Counter::load_mut(&mut data)?.set_authority(self.instruction_data.new_authority);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+/// 计数器 PDA 的种子前缀，完整种子为 [b"counter", user]
+pub const COUNTER_SEED: &[u8] = b"counter";
+
+#[repr(C)]
+pub struct Counter {
+    pub user: Address,      // 创建者，PDA 种子之一
+    pub authority: Address, // 允许修改计数器的账户
+    pub count: [u8; 8],    // u64，小端序
+    pub bump: [u8; 1],     // PDA bump seed
+}
+
+impl Counter {
+    pub const LEN: usize = size_of::<Address>()
+        + size_of::<Address>()
+        + size_of::<[u8; 8]>()
+        + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Counter::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != Counter::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn count(&self) -> u64 {
+        u64::from_le_bytes(self.count)
+    }
+
+    #[inline(always)]
+    pub fn set_count(&mut self, count: u64) {
+        self.count = count.to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_authority(&mut self, authority: Address) {
+        self.authority = authority;
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, user: Address, authority: Address, count: u64, bump: [u8; 1]) {
+        self.user = user;
+        self.authority = authority;
+        self.count = count.to_le_bytes();
+        self.bump = bump;
+    }
+}
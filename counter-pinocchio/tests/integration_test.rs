@@ -0,0 +1,336 @@
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+// 程序通过 target/deploy/counter_pinocchio.so 加载。
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use std::str::FromStr;
+
+const INITIALIZE: u8 = 0;
+const INCREMENT: u8 = 1;
+const DECREMENT: u8 = 2;
+const RESET: u8 = 3;
+const SET_AUTHORITY: u8 = 4;
+const USER_LAMPORTS: u64 = 10_000_000_000;
+
+// user (32) + authority (32) + count (8) + bump (1)
+const COUNTER_LEN: usize = 73;
+
+// CounterError
+const UNAUTHORIZED: u32 = 0;
+const OVERFLOW: u32 = 1;
+const UNDERFLOW: u32 = 2;
+const INVALID_COUNTER_ADDRESS: u32 = 3;
+
+fn program_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn counter_pda(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"counter", user.as_ref()], &program_id())
+}
+
+fn system_account() -> Account {
+    Account {
+        lamports: USER_LAMPORTS,
+        data: vec![],
+        owner: system_program::ID,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn counter_data(user: &Pubkey, authority: &Pubkey, count: u64, bump: u8) -> Vec<u8> {
+    let mut data = Vec::with_capacity(COUNTER_LEN);
+    data.extend_from_slice(user.as_ref());
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(&count.to_le_bytes());
+    data.push(bump);
+    data
+}
+
+fn program_test(users: &[&Pubkey]) -> ProgramTest {
+    // processor 传 None：加载 target/deploy 下的 SBF 程序
+    let mut program_test = ProgramTest::new("counter_pinocchio", program_id(), None);
+    program_test.prefer_bpf(true);
+    for user in users {
+        program_test.add_account(**user, system_account());
+    }
+    program_test
+}
+
+// 直接放入一个已有的计数器，用来测试溢出等边界情况
+fn add_counter(program_test: &mut ProgramTest, user: &Pubkey, authority: &Pubkey, count: u64) {
+    let (counter, bump) = counter_pda(user);
+    program_test.add_account(
+        counter,
+        Account {
+            lamports: Rent::default().minimum_balance(COUNTER_LEN),
+            data: counter_data(user, authority, count, bump),
+            owner: program_id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+fn initialize_instruction(user: &Pubkey, counter: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*counter, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![INITIALIZE],
+    }
+}
+
+fn counter_instruction(authority: &Pubkey, counter: &Pubkey, discriminator: u8, args: &[u8]) -> Instruction {
+    let mut data = vec![discriminator];
+    data.extend_from_slice(args);
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![AccountMeta::new(*authority, true), AccountMeta::new(*counter, false)],
+        data,
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn counter_state(context: &mut ProgramTestContext, counter: &Pubkey) -> (Pubkey, u64) {
+    let account = context.banks_client.get_account(*counter).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), COUNTER_LEN);
+    let authority = Pubkey::try_from(&account.data[32..64]).unwrap();
+    let count = u64::from_le_bytes(account.data[64..72].try_into().unwrap());
+    (authority, count)
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, expected)
+    );
+}
+
+#[tokio::test]
+async fn test_initialize() {
+    let user = Keypair::new();
+    let (counter, bump) = counter_pda(&user.pubkey());
+    let mut context = program_test(&[&user.pubkey()]).start_with_context().await;
+
+    process(&mut context, initialize_instruction(&user.pubkey(), &counter), &[&user])
+        .await
+        .unwrap();
+
+    let account = context.banks_client.get_account(counter).await.unwrap().unwrap();
+    assert_eq!(account.data, counter_data(&user.pubkey(), &user.pubkey(), 0, bump));
+    assert_eq!(account.lamports, Rent::default().minimum_balance(COUNTER_LEN));
+}
+
+#[tokio::test]
+async fn test_initialize_prefunded_pda() {
+    let user = Keypair::new();
+    let (counter, bump) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    // 任何人都可以提前往 PDA 转账，这不能让 initialize 永远失败
+    program_test.add_account(counter, Account { lamports: 1, ..system_account() });
+    let mut context = program_test.start_with_context().await;
+
+    process(&mut context, initialize_instruction(&user.pubkey(), &counter), &[&user])
+        .await
+        .unwrap();
+
+    let account = context.banks_client.get_account(counter).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data, counter_data(&user.pubkey(), &user.pubkey(), 0, bump));
+    assert_eq!(account.lamports, Rent::default().minimum_balance(COUNTER_LEN));
+}
+
+#[tokio::test]
+async fn test_initialize_twice_is_rejected() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 5);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process(&mut context, initialize_instruction(&user.pubkey(), &counter), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_initialize_wrong_pda_is_rejected() {
+    let user = Keypair::new();
+    let mut context = program_test(&[&user.pubkey()]).start_with_context().await;
+
+    let not_counter = Keypair::new().pubkey();
+    let result = process(&mut context, initialize_instruction(&user.pubkey(), &not_counter), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(INVALID_COUNTER_ADDRESS));
+}
+
+#[tokio::test]
+async fn test_increment_and_decrement() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut context = program_test(&[&user.pubkey()]).start_with_context().await;
+
+    process(&mut context, initialize_instruction(&user.pubkey(), &counter), &[&user])
+        .await
+        .unwrap();
+    let increment = counter_instruction(&user.pubkey(), &counter, INCREMENT, &10u64.to_le_bytes());
+    process(&mut context, increment, &[&user]).await.unwrap();
+    let decrement = counter_instruction(&user.pubkey(), &counter, DECREMENT, &3u64.to_le_bytes());
+    process(&mut context, decrement, &[&user]).await.unwrap();
+
+    assert_eq!(counter_state(&mut context, &counter).await.1, 7);
+}
+
+#[tokio::test]
+async fn test_increment_overflow_is_rejected() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), u64::MAX - 1);
+    let mut context = program_test.start_with_context().await;
+
+    let increment = counter_instruction(&user.pubkey(), &counter, INCREMENT, &2u64.to_le_bytes());
+    let result = process(&mut context, increment, &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(OVERFLOW));
+    assert_eq!(counter_state(&mut context, &counter).await.1, u64::MAX - 1);
+}
+
+#[tokio::test]
+async fn test_decrement_underflow_is_rejected() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 1);
+    let mut context = program_test.start_with_context().await;
+
+    let decrement = counter_instruction(&user.pubkey(), &counter, DECREMENT, &2u64.to_le_bytes());
+    let result = process(&mut context, decrement, &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNDERFLOW));
+    assert_eq!(counter_state(&mut context, &counter).await.1, 1);
+}
+
+#[tokio::test]
+async fn test_zero_amount_is_rejected() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 1);
+    let mut context = program_test.start_with_context().await;
+
+    let increment = counter_instruction(&user.pubkey(), &counter, INCREMENT, &0u64.to_le_bytes());
+    let result = process(&mut context, increment, &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidInstructionData);
+}
+
+#[tokio::test]
+async fn test_reset() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 42);
+    let mut context = program_test.start_with_context().await;
+
+    process(&mut context, counter_instruction(&user.pubkey(), &counter, RESET, &[]), &[&user])
+        .await
+        .unwrap();
+
+    assert_eq!(counter_state(&mut context, &counter).await.1, 0);
+}
+
+#[tokio::test]
+async fn test_set_authority() {
+    let user = Keypair::new();
+    let new_authority = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey(), &new_authority.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 1);
+    let mut context = program_test.start_with_context().await;
+
+    let set_authority = counter_instruction(
+        &user.pubkey(),
+        &counter,
+        SET_AUTHORITY,
+        new_authority.pubkey().as_ref(),
+    );
+    process(&mut context, set_authority, &[&user]).await.unwrap();
+    assert_eq!(counter_state(&mut context, &counter).await.0, new_authority.pubkey());
+
+    // 新 authority 可以修改计数器
+    let increment = counter_instruction(&new_authority.pubkey(), &counter, INCREMENT, &1u64.to_le_bytes());
+    process(&mut context, increment, &[&new_authority]).await.unwrap();
+    assert_eq!(counter_state(&mut context, &counter).await.1, 2);
+
+    // 原来的 user 不再有权限
+    let reset = counter_instruction(&user.pubkey(), &counter, RESET, &[]);
+    let result = process(&mut context, reset, &[&user]).await;
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn test_unauthorized_signer_is_rejected() {
+    let user = Keypair::new();
+    let attacker = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey(), &attacker.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 5);
+    let mut context = program_test.start_with_context().await;
+
+    for (discriminator, args) in [
+        (INCREMENT, 1u64.to_le_bytes().to_vec()),
+        (DECREMENT, 1u64.to_le_bytes().to_vec()),
+        (RESET, vec![]),
+        (SET_AUTHORITY, attacker.pubkey().to_bytes().to_vec()),
+    ] {
+        let instruction = counter_instruction(&attacker.pubkey(), &counter, discriminator, &args);
+        let result = process(&mut context, instruction, &[&attacker]).await;
+        assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+    }
+    assert_eq!(counter_state(&mut context, &counter).await, (user.pubkey(), 5));
+}
+
+#[tokio::test]
+async fn test_missing_signer_is_rejected() {
+    let user = Keypair::new();
+    let (counter, _) = counter_pda(&user.pubkey());
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_counter(&mut program_test, &user.pubkey(), &user.pubkey(), 5);
+    let mut context = program_test.start_with_context().await;
+
+    let mut reset = counter_instruction(&user.pubkey(), &counter, RESET, &[]);
+    reset.accounts[0].is_signer = false;
+    let result = process(&mut context, reset, &[]).await;
+
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
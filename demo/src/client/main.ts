@@ -28,20 +28,41 @@ function getTestKeypair(): Keypair {
   }
 }
 
+// 与 Rust 程序中的 DemoError 一一对应
+const PROGRAM_ERRORS: { [code: number]: string } = {
+  0: 'UserNotSigner',
+  1: 'UserNotWritable',
+  2: 'DataAccountMismatch',
+  3: 'DataAccountNotWritable',
+  4: 'DataAccountWrongOwner',
+  5: 'DataAccountAlreadyExists',
+  6: 'DataAccountNotInitialized',
+  7: 'InvalidSystemProgram',
+  8: 'InvalidRentSysvar',
+};
+
+function decodeProgramError(message: string): string | undefined {
+  const match = /custom program error: 0x([0-9a-f]+)/i.exec(message);
+  return match ? PROGRAM_ERRORS[parseInt(match[1], 16)] : undefined;
+}
+
 async function saveData() {
   const user = getTestKeypair();
   console.log('Using wallet:', user.publicKey.toBase58());
 
-  // 派生 PDA —— ⚠️ 请根据你的 Rust 程序逻辑修改 seeds！
+  // 派生 PDA：与 Rust 程序一致，seeds 为 [user]
   const [dataPda] = PublicKey.findProgramAddressSync(
-    [user.publicKey.toBuffer(), Buffer.from('data')], // 常见 seed 格式
+    [user.publicKey.toBuffer()],
     PROGRAM_ID
   );
 
   console.log('PDA address:', dataPda.toBase58());
 
-  // 指令数据
-  const instructionData = Buffer.from('Hello from TypeScript!');
+  // 指令数据：第一个字节是指令 tag（0 = Create, 1 = Update, 2 = Append, 3 = Close）
+  const instructionData = Buffer.concat([
+    Buffer.from([0x00]),
+    Buffer.from('Hello from TypeScript!'),
+  ]);
 
   // 构造 accounts
   const keys = [
@@ -70,6 +91,8 @@ async function saveData() {
   } catch (err: any) {
     console.error('❌ Transaction failed:');
     console.error(err.message);
+    const programError = decodeProgramError(err.message ?? '');
+    if (programError) console.error('Program error:', programError);
     if (err.logs) console.error('Logs:', err.logs);
     process.exit(1);
   }
@@ -1,10 +1,113 @@
 #![allow(unexpected_cfgs)]
 
-use solana_program::{account_info::{AccountInfo, next_account_info},entrypoint, entrypoint:: ProgramResult, program::invoke_signed, pubkey::Pubkey, rent::Rent, system_instruction::create_account, sysvar::Sysvar};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint,
+    entrypoint::ProgramResult,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction::{create_account, transfer},
+    system_program,
+    sysvar::{self, Sysvar},
+};
 
 entrypoint!(process_instruction);
 
-pub fn process_instruction(
+// Custom error codes, returned to the client as `custom program error: 0x..`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum DemoError {
+    UserNotSigner = 0,
+    UserNotWritable = 1,
+    DataAccountMismatch = 2,
+    DataAccountNotWritable = 3,
+    DataAccountWrongOwner = 4,
+    DataAccountAlreadyExists = 5,
+    DataAccountNotInitialized = 6,
+    InvalidSystemProgram = 7,
+    InvalidRentSysvar = 8,
+}
+
+impl From<DemoError> for ProgramError {
+    fn from(e: DemoError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+// Verify that the user signed and that the data account is the writable [user] PDA. Returns the bump seed.
+fn check_user_pda(
+    program_id: &Pubkey,
+    account_user: &AccountInfo,
+    account_data: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    if !account_user.is_signer {
+        return Err(DemoError::UserNotSigner.into());
+    }
+    if !account_user.is_writable {
+        return Err(DemoError::UserNotWritable.into());
+    }
+    let (pda, bump_seed) = Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id);
+    if *account_data.key != pda {
+        return Err(DemoError::DataAccountMismatch.into());
+    }
+    if !account_data.is_writable {
+        return Err(DemoError::DataAccountNotWritable.into());
+    }
+    Ok(bump_seed)
+}
+
+// Verify that an existing data account has been created by this program.
+fn check_initialized(program_id: &Pubkey, account_data: &AccountInfo) -> ProgramResult {
+    if account_data.lamports() == 0 {
+        return Err(DemoError::DataAccountNotInitialized.into());
+    }
+    if account_data.owner != program_id {
+        return Err(DemoError::DataAccountWrongOwner.into());
+    }
+    Ok(())
+}
+
+fn check_system_program(account_system: &AccountInfo) -> ProgramResult {
+    if *account_system.key != system_program::ID {
+        return Err(DemoError::InvalidSystemProgram.into());
+    }
+    Ok(())
+}
+
+fn check_rent_sysvar(account_rent: &AccountInfo) -> ProgramResult {
+    if *account_rent.key != sysvar::rent::ID {
+        return Err(DemoError::InvalidRentSysvar.into());
+    }
+    Ok(())
+}
+
+// Resize an existing data account to new_len, topping up rent from the user or refunding the surplus to the user.
+fn resize<'a>(
+    account_user: &AccountInfo<'a>,
+    account_data: &AccountInfo<'a>,
+    new_len: usize,
+) -> ProgramResult {
+    let rent_exemption = Rent::get()?.minimum_balance(new_len);
+    let balance = account_data.lamports();
+    if rent_exemption > balance {
+        invoke(
+            &transfer(account_user.key, account_data.key, rent_exemption - balance),
+            &[account_user.clone(), account_data.clone()],
+        )?;
+    }
+    if rent_exemption < balance {
+        // The data account is owned by this program, so its lamports can be moved directly.
+        let refund = balance - rent_exemption;
+        **account_data.try_borrow_mut_lamports()? -= refund;
+        **account_user.try_borrow_mut_lamports()? += refund;
+    }
+    account_data.realloc(new_len, false)?;
+    Ok(())
+}
+
+pub fn process_instruction_create(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     data: &[u8],
@@ -12,27 +115,105 @@ pub fn process_instruction(
     let accounts_iter = &mut accounts.iter();
     let account_user = next_account_info(accounts_iter)?;
     let account_data = next_account_info(accounts_iter)?;
-    let _ = next_account_info(accounts_iter)?; // Program system
-    let _ = next_account_info(accounts_iter)?; // Program sysvar rent
+    let account_system = next_account_info(accounts_iter)?; // Program system
+    let account_rent = next_account_info(accounts_iter)?; // Program sysvar rent
 
-    let rent_exemption = Rent::get()?.minimum_balance(data.len());
-    let bump_seed = Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id).1;
-
-    // Data account is not initialized. Create an account and write data into it.
-    if **account_data.try_borrow_lamports().unwrap() == 0 {
-       invoke_signed(
-            &create_account(
-                account_user.key,
-                account_data.key,
-                rent_exemption,
-                data.len() as u64,
-                program_id,
-            ),
-            accounts,
-            &[&[&account_user.key.to_bytes(), &[bump_seed]]],
-        )?;
-        account_data.data.borrow_mut().copy_from_slice(data);
-        return Ok(());
+    let bump_seed = check_user_pda(program_id, account_user, account_data)?;
+    check_system_program(account_system)?;
+    check_rent_sysvar(account_rent)?;
+    if account_data.lamports() != 0 {
+        return Err(DemoError::DataAccountAlreadyExists.into());
     }
+
+    let rent_exemption = Rent::get()?.minimum_balance(data.len());
+    invoke_signed(
+        &create_account(
+            account_user.key,
+            account_data.key,
+            rent_exemption,
+            data.len() as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[&account_user.key.to_bytes(), &[bump_seed]]],
+    )?;
+    account_data.data.borrow_mut().copy_from_slice(data);
+    Ok(())
+}
+
+pub fn process_instruction_update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = next_account_info(accounts_iter)?;
+    let account_data = next_account_info(accounts_iter)?;
+    let account_system = next_account_info(accounts_iter)?; // Program system
+
+    check_user_pda(program_id, account_user, account_data)?;
+    check_system_program(account_system)?;
+    check_initialized(program_id, account_data)?;
+
+    resize(account_user, account_data, data.len())?;
+    account_data.data.borrow_mut().copy_from_slice(data);
+    Ok(())
+}
+
+pub fn process_instruction_append(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = next_account_info(accounts_iter)?;
+    let account_data = next_account_info(accounts_iter)?;
+    let account_system = next_account_info(accounts_iter)?; // Program system
+
+    check_user_pda(program_id, account_user, account_data)?;
+    check_system_program(account_system)?;
+    check_initialized(program_id, account_data)?;
+
+    let old_len = account_data.data_len();
+    let new_len = old_len.checked_add(data.len()).ok_or(ProgramError::InvalidInstructionData)?;
+    resize(account_user, account_data, new_len)?;
+    account_data.data.borrow_mut()[old_len..].copy_from_slice(data);
+    Ok(())
+}
+
+pub fn process_instruction_close(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _: &[u8],
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = next_account_info(accounts_iter)?;
+    let account_data = next_account_info(accounts_iter)?;
+
+    check_user_pda(program_id, account_user, account_data)?;
+    check_initialized(program_id, account_data)?;
+
+    // Return all lamports to the user and hand the empty account back to the system program,
+    // so that it can be created again later.
+    let balance = account_data.lamports();
+    **account_data.try_borrow_mut_lamports()? = 0;
+    **account_user.try_borrow_mut_lamports()? += balance;
+    account_data.realloc(0, false)?;
+    account_data.assign(&system_program::ID);
     Ok(())
 }
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let (tag, data) = data.split_first().ok_or(ProgramError::InvalidInstructionData)?;
+    match tag {
+        0x00 => process_instruction_create(program_id, accounts, data),
+        0x01 => process_instruction_update(program_id, accounts, data),
+        0x02 => process_instruction_append(program_id, accounts, data),
+        0x03 => process_instruction_close(program_id, accounts, data),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
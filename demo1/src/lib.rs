@@ -1,19 +1,157 @@
 #![allow(unexpected_cfgs)]
 use solana_program::sysvar::Sysvar;
 solana_program::entrypoint!(process_instruction);
+
+// Custom error codes, returned to the client as `custom program error: 0x..`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LedgerError {
+    Unauthorized = 0,
+}
+
+impl From<LedgerError> for solana_program::program_error::ProgramError {
+    fn from(e: LedgerError) -> Self {
+        solana_program::program_error::ProgramError::Custom(e as u32)
+    }
+}
+
+// The only account allowed to mint. demo1 is the minimal ledger and deliberately has no config account, so the admin
+// is compiled in and rotating it means redeploying. demo2 moves the mint authority into a Config PDA instead.
+pub const ADMIN: solana_program::pubkey::Pubkey =
+    solana_program::pubkey!("6ASf5EcmmEHTgDJ4X4ZT5vT6iHVJBXPg5AN5YoTCpGWt");
+
+// Each balance account is the [owner] PDA holding a little-endian u64.
+pub const BALANCE_LEN: usize = 8;
+
+fn parse_amount(data: &[u8]) -> Result<u64, solana_program::program_error::ProgramError> {
+    let buf: [u8; 8] = data
+        .try_into()
+        .map_err(|_| solana_program::program_error::ProgramError::InvalidInstructionData)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+// Check that account_pda is the balance PDA of account_owner, and return its bump seed.
+fn check_balance_pda(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_owner: &solana_program::account_info::AccountInfo,
+    account_pda: &solana_program::account_info::AccountInfo,
+) -> Result<u8, solana_program::program_error::ProgramError> {
+    let (pda, bump_seed) =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_owner.key.to_bytes()], program_id);
+    if *account_pda.key != pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    Ok(bump_seed)
+}
+
+// Create the balance PDA of account_owner with a zero balance if it does not exist yet. The payer funds the rent.
+fn create_balance_if_needed<'a>(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_payer: &solana_program::account_info::AccountInfo<'a>,
+    account_owner: &solana_program::account_info::AccountInfo<'a>,
+    account_pda: &solana_program::account_info::AccountInfo<'a>,
+    account_system: &solana_program::account_info::AccountInfo<'a>,
+) -> solana_program::entrypoint::ProgramResult {
+    let bump_seed = check_balance_pda(program_id, account_owner, account_pda)?;
+    if account_pda.lamports() != 0 {
+        return Ok(());
+    }
+    let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(BALANCE_LEN);
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::create_account(
+            account_payer.key,
+            account_pda.key,
+            rent_exemption,
+            BALANCE_LEN as u64,
+            program_id,
+        ),
+        &[account_payer.clone(), account_pda.clone(), account_system.clone()],
+        &[&[&account_owner.key.to_bytes(), &[bump_seed]]],
+    )?;
+    account_pda.data.borrow_mut().copy_from_slice(&u64::MIN.to_le_bytes());
+    Ok(())
+}
+
+fn read_balance(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_pda: &solana_program::account_info::AccountInfo,
+) -> Result<u64, solana_program::program_error::ProgramError> {
+    if account_pda.owner != program_id {
+        return Err(solana_program::program_error::ProgramError::IncorrectProgramId);
+    }
+    let buf: [u8; 8] = account_pda.data.borrow()[..]
+        .try_into()
+        .map_err(|_| solana_program::program_error::ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn write_balance(account_pda: &solana_program::account_info::AccountInfo, balance: u64) {
+    account_pda.data.borrow_mut().copy_from_slice(&balance.to_le_bytes());
+}
+
 pub fn process_instruction_mint(
-    _: &solana_program::pubkey::Pubkey,
-    _: &[solana_program::account_info::AccountInfo],
-    _: &[u8],
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
 ) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_admin = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_system = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+
+    if !account_admin.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    if *account_admin.key != ADMIN {
+        return Err(LedgerError::Unauthorized.into());
+    }
+    let amount = parse_amount(data)?;
+
+    // Mint.
+    create_balance_if_needed(program_id, account_admin, account_into, account_into_pda, account_system)?;
+    let old = read_balance(program_id, account_into_pda)?;
+    let new = old
+        .checked_add(amount)
+        .ok_or(solana_program::program_error::ProgramError::ArithmeticOverflow)?;
+    write_balance(account_into_pda, new);
     Ok(())
 }
 
 pub fn process_instruction_transfer(
-    _: &solana_program::pubkey::Pubkey,
-    _: &[solana_program::account_info::AccountInfo],
-    _: &[u8],
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
 ) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_system = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    check_balance_pda(program_id, account_user, account_user_pda)?;
+    let amount = parse_amount(data)?;
+
+    // Transfer.
+    create_balance_if_needed(program_id, account_user, account_into, account_into_pda, account_system)?;
+    let old_user = read_balance(program_id, account_user_pda)?;
+    let new_user = old_user
+        .checked_sub(amount)
+        .ok_or(solana_program::program_error::ProgramError::InsufficientFunds)?;
+    if account_user_pda.key == account_into_pda.key {
+        // Sending to yourself leaves the balance unchanged.
+        return Ok(());
+    }
+    let old_into = read_balance(program_id, account_into_pda)?;
+    let new_into = old_into
+        .checked_add(amount)
+        .ok_or(solana_program::program_error::ProgramError::ArithmeticOverflow)?;
+    write_balance(account_user_pda, new_user);
+    write_balance(account_into_pda, new_into);
     Ok(())
 }
 
@@ -22,10 +160,9 @@ pub fn process_instruction(
     accounts: &[solana_program::account_info::AccountInfo],
     data: &[u8],
 ) -> solana_program::entrypoint::ProgramResult {
-    assert!(data.len() >= 1);
-    match data[0] {
-        0x00 => process_instruction_mint(program_id, accounts, &data[1..]),
-        0x01 => process_instruction_transfer(program_id, accounts, &data[1..]),
-        _ => unreachable!(),
+    match data.split_first() {
+        Some((0x00, data)) => process_instruction_mint(program_id, accounts, data),
+        Some((0x01, data)) => process_instruction_transfer(program_id, accounts, data),
+        _ => Err(solana_program::program_error::ProgramError::InvalidInstructionData),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,158 @@
+// 程序以原生方式运行（processor!），直接 cargo test 即可。
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+const MINT: u8 = 0;
+const TRANSFER: u8 = 1;
+const USER_LAMPORTS: u64 = 10_000_000_000;
+
+// LedgerError
+const UNAUTHORIZED: u32 = 0;
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array([2; 32])
+}
+
+fn balance_pda(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&user.to_bytes()], &program_id()).0
+}
+
+fn system_account() -> Account {
+    Account { lamports: USER_LAMPORTS, data: vec![], owner: system_program::ID, executable: false, rent_epoch: 0 }
+}
+
+fn balance_account(owner: Pubkey, amount: u64) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(demo1::BALANCE_LEN),
+        data: amount.to_le_bytes().to_vec(),
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn program_test(users: &[&Pubkey]) -> ProgramTest {
+    let mut program_test = ProgramTest::new("demo1", program_id(), processor!(demo1::process_instruction));
+    for user in users {
+        program_test.add_account(**user, system_account());
+    }
+    program_test
+}
+
+// ADMIN 的私钥不在测试里，余额账户直接放入
+fn add_balance(program_test: &mut ProgramTest, user: &Pubkey, amount: u64) {
+    program_test.add_account(balance_pda(user), balance_account(program_id(), amount));
+}
+
+fn instruction(tag: u8, amount: u64, accounts: Vec<AccountMeta>) -> Instruction {
+    let mut data = vec![tag];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction { program_id: program_id(), accounts, data }
+}
+
+fn mint_instruction(admin: &Pubkey, is_signer: bool, into: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*admin, is_signer),
+        AccountMeta::new_readonly(*into, false),
+        AccountMeta::new(balance_pda(into), false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    instruction(MINT, amount, accounts)
+}
+
+fn transfer_instruction(user: &Pubkey, into: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(balance_pda(user), false),
+        AccountMeta::new_readonly(*into, false),
+        AccountMeta::new(balance_pda(into), false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    instruction(TRANSFER, amount, accounts)
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn balance_of(context: &mut ProgramTestContext, user: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(balance_pda(user)).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    u64::from_le_bytes(account.data[..].try_into().unwrap())
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(result.unwrap_err().unwrap(), TransactionError::InstructionError(0, expected));
+}
+
+#[tokio::test]
+async fn test_mint_by_non_admin_is_rejected() {
+    let user = Keypair::new();
+    let mut context = program_test(&[&user.pubkey()]).start_with_context().await;
+
+    let result = process(&mut context, mint_instruction(&user.pubkey(), true, &user.pubkey(), 100), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+    assert!(context.banks_client.get_account(balance_pda(&user.pubkey())).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_mint_without_admin_signature_is_rejected() {
+    let into = Keypair::new().pubkey();
+    let mut context = program_test(&[]).start_with_context().await;
+
+    // 传入 ADMIN 的地址但没有它的签名
+    let result = process(&mut context, mint_instruction(&demo1::ADMIN, false, &into, 100), &[]).await;
+
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+}
+
+#[tokio::test]
+async fn test_transfer() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_balance(&mut program_test, &user.pubkey(), 100);
+    let mut context = program_test.start_with_context().await;
+
+    // 接收方的余额账户不存在时由发送方创建
+    process(&mut context, transfer_instruction(&user.pubkey(), &into, 30), &[&user]).await.unwrap();
+
+    assert_eq!(balance_of(&mut context, &user.pubkey()).await, 70);
+    assert_eq!(balance_of(&mut context, &into).await, 30);
+}
+
+#[tokio::test]
+async fn test_transfer_from_account_not_owned_by_program_is_rejected() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    // 地址是正确的 PDA，但账户不归本程序所有，里面的数据不能当作余额
+    program_test.add_account(balance_pda(&user.pubkey()), balance_account(system_program::ID, 100));
+    let mut context = program_test.start_with_context().await;
+
+    let result = process(&mut context, transfer_instruction(&user.pubkey(), &into, 30), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::IncorrectProgramId);
+}
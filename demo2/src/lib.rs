@@ -1,5 +1,244 @@
 #![allow(unexpected_cfgs)]
+use solana_program::sysvar::Sysvar;
 solana_program::entrypoint!(process_instruction);
+
+// Custom error codes, returned to the client as `custom program error: 0x..`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LedgerError {
+    InsufficientBalance = 0,
+    Overflow = 1,
+    BadDataLength = 2,
+    Unauthorized = 3,
+    UnknownInstruction = 4,
+    SelfTransfer = 5,
+    InsufficientAllowance = 6,
+    OutdatedBalanceAccount = 7,
+    InsufficientSupply = 8,
+}
+
+impl From<LedgerError> for solana_program::program_error::ProgramError {
+    fn from(e: LedgerError) -> Self {
+        solana_program::program_error::ProgramError::Custom(e as u32)
+    }
+}
+
+// Instruction amounts are big-endian u64. This is the wire format existing clients already send, so it stays
+// big-endian even though stored balances and allowances are now little-endian.
+fn parse_amount(data: &[u8]) -> Result<u64, solana_program::program_error::ProgramError> {
+    let buf: [u8; 8] = data.try_into().map_err(|_| LedgerError::BadDataLength)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+// Balance accounts are [owner] PDAs with the layout
+//   tag (1) | version (1) | owner (32) | bump (1) | amount (u64, little endian)
+// Version 0 accounts were a bare big-endian u64 and can be upgraded with the migrate instruction.
+pub const BALANCE_TAG: u8 = 1;
+pub const BALANCE_VERSION: u8 = 1;
+pub const BALANCE_LEN: usize = 1 + 1 + 32 + 1 + 8;
+pub const BALANCE_LEN_V0: usize = 8;
+
+fn read_balance(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_pda: &solana_program::account_info::AccountInfo,
+) -> Result<u64, solana_program::program_error::ProgramError> {
+    if account_pda.owner != program_id {
+        return Err(solana_program::program_error::ProgramError::UninitializedAccount);
+    }
+    let data = account_pda.try_borrow_data()?;
+    if data.len() == BALANCE_LEN_V0 {
+        return Err(LedgerError::OutdatedBalanceAccount.into());
+    }
+    if data.len() != BALANCE_LEN || data[0] != BALANCE_TAG {
+        return Err(solana_program::program_error::ProgramError::InvalidAccountData);
+    }
+    if data[1] != BALANCE_VERSION {
+        return Err(LedgerError::OutdatedBalanceAccount.into());
+    }
+    Ok(u64::from_le_bytes(data[35..43].try_into().unwrap()))
+}
+
+fn write_balance(
+    account_pda: &solana_program::account_info::AccountInfo,
+    balance: u64,
+) -> solana_program::entrypoint::ProgramResult {
+    account_pda.try_borrow_mut_data()?[35..43].copy_from_slice(&balance.to_le_bytes());
+    Ok(())
+}
+
+fn write_balance_header(
+    account_pda: &solana_program::account_info::AccountInfo,
+    owner: &solana_program::pubkey::Pubkey,
+    bump_seed: u8,
+) -> solana_program::entrypoint::ProgramResult {
+    let mut data = account_pda.try_borrow_mut_data()?;
+    data[0] = BALANCE_TAG;
+    data[1] = BALANCE_VERSION;
+    data[2..34].copy_from_slice(&owner.to_bytes());
+    data[34] = bump_seed;
+    Ok(())
+}
+
+// Create the balance account of account_owner with a zero balance. The payer funds the rent.
+fn create_balance<'a>(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_payer: &solana_program::account_info::AccountInfo<'a>,
+    account_owner: &solana_program::account_info::AccountInfo<'a>,
+    account_pda: &solana_program::account_info::AccountInfo<'a>,
+    bump_seed: u8,
+    accounts: &[solana_program::account_info::AccountInfo<'a>],
+) -> solana_program::entrypoint::ProgramResult {
+    let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(BALANCE_LEN);
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::create_account(
+            account_payer.key,
+            account_pda.key,
+            rent_exemption,
+            BALANCE_LEN as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[&account_owner.key.to_bytes(), &[bump_seed]]],
+    )?;
+    write_balance_header(account_pda, account_owner.key, bump_seed)?;
+    write_balance(account_pda, u64::MIN)
+}
+
+// Allowance accounts are [owner, delegate] PDAs holding a little-endian u64.
+pub const ALLOWANCE_LEN: usize = 8;
+
+fn read_allowance(
+    program_id: &solana_program::pubkey::Pubkey,
+    account_allowance_pda: &solana_program::account_info::AccountInfo,
+) -> Result<u64, solana_program::program_error::ProgramError> {
+    if account_allowance_pda.owner != program_id {
+        return Err(LedgerError::InsufficientAllowance.into());
+    }
+    let buf: [u8; 8] = account_allowance_pda.try_borrow_data()?[..]
+        .try_into()
+        .map_err(|_| solana_program::program_error::ProgramError::InvalidAccountData)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn write_allowance(
+    account_allowance_pda: &solana_program::account_info::AccountInfo,
+    allowance: u64,
+) -> solana_program::entrypoint::ProgramResult {
+    account_allowance_pda.try_borrow_mut_data()?.copy_from_slice(&allowance.to_le_bytes());
+    Ok(())
+}
+
+// The only account allowed to initialize the config. It becomes the first mint authority and can hand that role over
+// with set_authority afterwards.
+pub const ADMIN: solana_program::pubkey::Pubkey =
+    solana_program::pubkey!("6ASf5EcmmEHTgDJ4X4ZT5vT6iHVJBXPg5AN5YoTCpGWt");
+
+// Seed of the config PDA, which stores the mint authority and the total supply.
+pub const CONFIG_SEED: &[u8] = b"config";
+// authority (32) + total supply (u64, little endian) + bump (1)
+pub const CONFIG_LEN: usize = 32 + 8 + 1;
+
+pub struct Config {
+    pub authority: solana_program::pubkey::Pubkey,
+    pub supply: u64,
+    pub bump: u8,
+}
+
+impl Config {
+    pub fn load(
+        program_id: &solana_program::pubkey::Pubkey,
+        account_config: &solana_program::account_info::AccountInfo,
+    ) -> Result<Self, solana_program::program_error::ProgramError> {
+        if account_config.owner != program_id {
+            return Err(solana_program::program_error::ProgramError::UninitializedAccount);
+        }
+        let data = account_config.try_borrow_data()?;
+        if data.len() != CONFIG_LEN {
+            return Err(solana_program::program_error::ProgramError::InvalidAccountData);
+        }
+        let config = Self {
+            authority: solana_program::pubkey::Pubkey::new_from_array(data[0..32].try_into().unwrap()),
+            supply: u64::from_le_bytes(data[32..40].try_into().unwrap()),
+            bump: data[40],
+        };
+        let pda = solana_program::pubkey::Pubkey::create_program_address(&[CONFIG_SEED, &[config.bump]], program_id)?;
+        if *account_config.key != pda {
+            return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+        }
+        Ok(config)
+    }
+
+    pub fn store(
+        &self,
+        account_config: &solana_program::account_info::AccountInfo,
+    ) -> solana_program::entrypoint::ProgramResult {
+        let mut data = account_config.try_borrow_mut_data()?;
+        data[0..32].copy_from_slice(&self.authority.to_bytes());
+        data[32..40].copy_from_slice(&self.supply.to_le_bytes());
+        data[40] = self.bump;
+        Ok(())
+    }
+}
+
+pub fn process_instruction_initialize(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    _: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_config = solana_program::account_info::next_account_info(accounts_iter)?;
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    // Otherwise anyone could front-run the deployer and take over minting.
+    if *account_user.key != ADMIN {
+        return Err(LedgerError::Unauthorized.into());
+    }
+    let (pda, bump_seed) = solana_program::pubkey::Pubkey::find_program_address(&[CONFIG_SEED], program_id);
+    if *account_config.key != pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    if **account_config.try_borrow_lamports()? != 0 {
+        return Err(solana_program::program_error::ProgramError::AccountAlreadyInitialized);
+    }
+
+    // The admin becomes the first mint authority.
+    let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(CONFIG_LEN);
+    solana_program::program::invoke_signed(
+        &solana_program::system_instruction::create_account(
+            account_user.key,
+            account_config.key,
+            rent_exemption,
+            CONFIG_LEN as u64,
+            program_id,
+        ),
+        accounts,
+        &[&[CONFIG_SEED, &[bump_seed]]],
+    )?;
+    Config { authority: *account_user.key, supply: 0, bump: bump_seed }.store(account_config)
+}
+
+pub fn process_instruction_set_authority(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_config = solana_program::account_info::next_account_info(accounts_iter)?;
+
+    let mut config = Config::load(program_id, account_config)?;
+    if !account_user.is_signer || *account_user.key != config.authority {
+        return Err(LedgerError::Unauthorized.into());
+    }
+    let new_authority: [u8; 32] = data.try_into().map_err(|_| LedgerError::BadDataLength)?;
+    config.authority = solana_program::pubkey::Pubkey::new_from_array(new_authority);
+    config.store(account_config)?;
+    Ok(())
+}
 pub fn process_instruction_mint(
     program_id: &solana_program::pubkey::Pubkey,
     accounts: &[solana_program::account_info::AccountInfo],
@@ -8,37 +247,31 @@ pub fn process_instruction_mint(
     let accounts_iter = &mut accounts.iter();
     let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
     let account_user_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_config = solana_program::account_info::next_account_info(accounts_iter)?;
     let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
     let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program sysvar rent
 
-    assert_eq!(*account_user.key, solana_program::pubkey!("6ASf5EcmmEHTgDJ4X4ZT5vT6iHVJBXPg5AN5YoTCpGWt"));
+    let mut config = Config::load(program_id, account_config)?;
+    if !account_user.is_signer || *account_user.key != config.authority {
+        return Err(LedgerError::Unauthorized.into());
+    }
+    let inc = parse_amount(data)?;
 
-    if **account_user_pda.try_borrow_lamports().unwrap() == 0 {
-        let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(8);
-        let bump_seed =
-            solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id).1;
-        solana_program::program::invoke_signed(
-            &solana_program::system_instruction::create_account(
-                account_user.key,
-                account_user_pda.key,
-                rent_exemption,
-                8,
-                program_id,
-            ),
-            accounts,
-            &[&[&account_user.key.to_bytes(), &[bump_seed]]],
-        )?;
-        account_user_pda.data.borrow_mut().copy_from_slice(&u64::MIN.to_be_bytes());
-    }
-      // Mint.
-      let mut buf = [0u8; 8];
-      buf.copy_from_slice(&account_user_pda.data.borrow());
-      let old = u64::from_be_bytes(buf);
-      buf.copy_from_slice(&data);
-      let inc = u64::from_be_bytes(buf);
-      let new = old.checked_add(inc).unwrap();
-      account_user_pda.data.borrow_mut().copy_from_slice(&new.to_be_bytes());
-        Ok(())
+    let (account_need_pda, bump_seed) =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id);
+    if account_user_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    if **account_user_pda.try_borrow_lamports()? == 0 {
+        create_balance(program_id, account_user, account_user, account_user_pda, bump_seed, accounts)?;
+    }
+    // Mint.
+    let old = read_balance(program_id, account_user_pda)?;
+    let new = old.checked_add(inc).ok_or(LedgerError::Overflow)?;
+    config.supply = config.supply.checked_add(inc).ok_or(LedgerError::Overflow)?;
+    write_balance(account_user_pda, new)?;
+    config.store(account_config)?;
+    Ok(())
 }
 
 pub fn process_instruction_transfer(
@@ -56,50 +289,270 @@ pub fn process_instruction_transfer(
     let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program sysvar rent
 
 
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
     let account_need_pda =
     solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id).0;
-    assert_eq!(account_user_pda.key, &account_need_pda);
-    if **account_into_pda.try_borrow_lamports().unwrap() == 0 {
-        let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(8);
-        let bump_seed =
-            solana_program::pubkey::Pubkey::find_program_address(&[&account_into.key.to_bytes()], program_id).1;
+    if account_user_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    let (account_into_need_pda, bump_seed) =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_into.key.to_bytes()], program_id);
+    if account_into_pda.key != &account_into_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    // Both balances are read before either is written, so a self-transfer would mint tokens.
+    if account_user.key == account_into.key {
+        return Err(LedgerError::SelfTransfer.into());
+    }
+    let inc = parse_amount(data)?;
+    if **account_into_pda.try_borrow_lamports()? == 0 {
+        create_balance(program_id, account_user, account_into, account_into_pda, bump_seed, accounts)?;
+    }
+    // Transfer.
+    let old_user = read_balance(program_id, account_user_pda)?;
+    let old_into = read_balance(program_id, account_into_pda)?;
+    let new_user = old_user.checked_sub(inc).ok_or(LedgerError::InsufficientBalance)?;
+    let new_into = old_into.checked_add(inc).ok_or(LedgerError::Overflow)?;
+    write_balance(account_user_pda, new_user)?;
+    write_balance(account_into_pda, new_into)?;
+    Ok(())
+}
+
+pub fn process_instruction_burn(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_config = solana_program::account_info::next_account_info(accounts_iter)?;
+
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let account_need_pda =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id).0;
+    if account_user_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    let mut config = Config::load(program_id, account_config)?;
+    let dec = parse_amount(data)?;
+
+    // Burn.
+    let old = read_balance(program_id, account_user_pda)?;
+    let new = old.checked_sub(dec).ok_or(LedgerError::InsufficientBalance)?;
+    // Balances created before the supply was tracked are not counted in it, so burning them can exceed the supply.
+    config.supply = config.supply.checked_sub(dec).ok_or(LedgerError::InsufficientSupply)?;
+    write_balance(account_user_pda, new)?;
+    config.store(account_config)?;
+    Ok(())
+}
+
+pub fn process_instruction_approve(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_delegate = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_allowance_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program sysvar rent
+
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (account_need_pda, bump_seed) = solana_program::pubkey::Pubkey::find_program_address(
+        &[&account_user.key.to_bytes(), &account_delegate.key.to_bytes()],
+        program_id,
+    );
+    if account_allowance_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    let allowance = parse_amount(data)?;
+
+    if **account_allowance_pda.try_borrow_lamports()? == 0 {
+        let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(ALLOWANCE_LEN);
         solana_program::program::invoke_signed(
             &solana_program::system_instruction::create_account(
                 account_user.key,
-                account_into_pda.key,
+                account_allowance_pda.key,
                 rent_exemption,
-                8,
+                ALLOWANCE_LEN as u64,
                 program_id,
             ),
             accounts,
-            &[&[&account_into.key.to_bytes(), &[bump_seed]]],
+            &[&[&account_user.key.to_bytes(), &account_delegate.key.to_bytes(), &[bump_seed]]],
         )?;
-        account_into_pda.data.borrow_mut().copy_from_slice(&u64::MIN.to_be_bytes());
-    }
-      // Transfer.
-      let mut buf = [0u8; 8];
-      buf.copy_from_slice(&account_user_pda.data.borrow());
-      let old_user = u64::from_be_bytes(buf);
-      buf.copy_from_slice(&account_into_pda.data.borrow());
-      let old_into = u64::from_be_bytes(buf);
-      buf.copy_from_slice(&data);
-      let inc = u64::from_be_bytes(buf);
-      let new_user = old_user.checked_sub(inc).unwrap();
-      let new_into = old_into.checked_add(inc).unwrap();
-      account_user_pda.data.borrow_mut().copy_from_slice(&new_user.to_be_bytes());
-      account_into_pda.data.borrow_mut().copy_from_slice(&new_into.to_be_bytes());
+    }
+    // Approve replaces any previous allowance.
+    write_allowance(account_allowance_pda, allowance)
+}
+
+pub fn process_instruction_revoke(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    _: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_delegate = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_allowance_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+
+    if !account_user.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let account_need_pda = solana_program::pubkey::Pubkey::find_program_address(
+        &[&account_user.key.to_bytes(), &account_delegate.key.to_bytes()],
+        program_id,
+    )
+    .0;
+    if account_allowance_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    if account_allowance_pda.owner != program_id {
+        return Err(solana_program::program_error::ProgramError::UninitializedAccount);
+    }
+
+    // Close the allowance account and refund its rent to the owner.
+    let lamports = account_allowance_pda.lamports();
+    **account_allowance_pda.try_borrow_mut_lamports()? = 0;
+    **account_user.try_borrow_mut_lamports()? = account_user
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(LedgerError::Overflow)?;
+    account_allowance_pda.realloc(0, false)?;
+    account_allowance_pda.assign(&solana_program::system_program::ID);
     Ok(())
 }
 
+pub fn process_instruction_transfer_from(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    data: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_delegate = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_into_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_allowance_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program sysvar rent
+
+    if !account_delegate.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let account_need_pda =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id).0;
+    if account_user_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    let (account_into_need_pda, bump_seed) =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_into.key.to_bytes()], program_id);
+    if account_into_pda.key != &account_into_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    let account_allowance_need_pda = solana_program::pubkey::Pubkey::find_program_address(
+        &[&account_user.key.to_bytes(), &account_delegate.key.to_bytes()],
+        program_id,
+    )
+    .0;
+    if account_allowance_pda.key != &account_allowance_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    if account_user.key == account_into.key {
+        return Err(LedgerError::SelfTransfer.into());
+    }
+    let inc = parse_amount(data)?;
+
+    // Spend the allowance first, so an unapproved delegate never creates accounts.
+    let old_allowance = read_allowance(program_id, account_allowance_pda)?;
+    let new_allowance = old_allowance.checked_sub(inc).ok_or(LedgerError::InsufficientAllowance)?;
+
+    if **account_into_pda.try_borrow_lamports()? == 0 {
+        create_balance(program_id, account_delegate, account_into, account_into_pda, bump_seed, accounts)?;
+    }
+    // Transfer.
+    let old_user = read_balance(program_id, account_user_pda)?;
+    let old_into = read_balance(program_id, account_into_pda)?;
+    let new_user = old_user.checked_sub(inc).ok_or(LedgerError::InsufficientBalance)?;
+    let new_into = old_into.checked_add(inc).ok_or(LedgerError::Overflow)?;
+    write_allowance(account_allowance_pda, new_allowance)?;
+    write_balance(account_user_pda, new_user)?;
+    write_balance(account_into_pda, new_into)?;
+    Ok(())
+}
+
+pub fn process_instruction_migrate(
+    program_id: &solana_program::pubkey::Pubkey,
+    accounts: &[solana_program::account_info::AccountInfo],
+    _: &[u8],
+) -> solana_program::entrypoint::ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let account_payer = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user = solana_program::account_info::next_account_info(accounts_iter)?;
+    let account_user_pda = solana_program::account_info::next_account_info(accounts_iter)?;
+    let _ = solana_program::account_info::next_account_info(accounts_iter)?; // Program system
+
+    if !account_payer.is_signer {
+        return Err(solana_program::program_error::ProgramError::MissingRequiredSignature);
+    }
+    let (account_need_pda, bump_seed) =
+        solana_program::pubkey::Pubkey::find_program_address(&[&account_user.key.to_bytes()], program_id);
+    if account_user_pda.key != &account_need_pda {
+        return Err(solana_program::program_error::ProgramError::InvalidSeeds);
+    }
+    if account_user_pda.owner != program_id {
+        return Err(solana_program::program_error::ProgramError::UninitializedAccount);
+    }
+    if account_user_pda.data_len() != BALANCE_LEN_V0 {
+        // Already on the current layout, nothing to do.
+        read_balance(program_id, account_user_pda)?;
+        return Ok(());
+    }
+
+    // Migrate. Anyone may pay for the upgrade, the balance itself is unchanged.
+    let buf: [u8; 8] = account_user_pda.try_borrow_data()?[..].try_into().unwrap();
+    let balance = u64::from_be_bytes(buf);
+    let rent_exemption = solana_program::rent::Rent::get()?.minimum_balance(BALANCE_LEN);
+    let lamports = account_user_pda.lamports();
+    if rent_exemption > lamports {
+        solana_program::program::invoke(
+            &solana_program::system_instruction::transfer(
+                account_payer.key,
+                account_user_pda.key,
+                rent_exemption - lamports,
+            ),
+            accounts,
+        )?;
+    }
+    account_user_pda.realloc(BALANCE_LEN, true)?;
+    write_balance_header(account_user_pda, account_user.key, bump_seed)?;
+    write_balance(account_user_pda, balance)
+}
+
 pub fn process_instruction(
     program_id: &solana_program::pubkey::Pubkey,
     accounts: &[solana_program::account_info::AccountInfo],
     data: &[u8],
 ) -> solana_program::entrypoint::ProgramResult {
-    assert!(data.len() >= 1);
-    match data[0] {
-        0x00 => process_instruction_mint(program_id, accounts, &data[1..]),
-        0x01 => process_instruction_transfer(program_id, accounts, &data[1..]),
-        _ => unreachable!(),
+    let (tag, data) = data.split_first().ok_or(LedgerError::UnknownInstruction)?;
+    match tag {
+        0x00 => process_instruction_mint(program_id, accounts, data),
+        0x01 => process_instruction_transfer(program_id, accounts, data),
+        0x02 => process_instruction_initialize(program_id, accounts, data),
+        0x03 => process_instruction_set_authority(program_id, accounts, data),
+        0x04 => process_instruction_burn(program_id, accounts, data),
+        0x05 => process_instruction_approve(program_id, accounts, data),
+        0x06 => process_instruction_revoke(program_id, accounts, data),
+        0x07 => process_instruction_transfer_from(program_id, accounts, data),
+        0x08 => process_instruction_migrate(program_id, accounts, data),
+        _ => Err(LedgerError::UnknownInstruction.into()),
     }
 }
\ No newline at end of file
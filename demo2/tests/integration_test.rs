@@ -0,0 +1,364 @@
+// 程序以原生方式运行（processor!），直接 cargo test 即可。
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+const MINT: u8 = 0;
+const TRANSFER: u8 = 1;
+const INITIALIZE: u8 = 2;
+const BURN: u8 = 4;
+const APPROVE: u8 = 5;
+const TRANSFER_FROM: u8 = 7;
+const MIGRATE: u8 = 8;
+const USER_LAMPORTS: u64 = 10_000_000_000;
+
+// LedgerError
+const INSUFFICIENT_BALANCE: u32 = 0;
+const UNAUTHORIZED: u32 = 3;
+const INSUFFICIENT_ALLOWANCE: u32 = 6;
+const OUTDATED_BALANCE_ACCOUNT: u32 = 7;
+const INSUFFICIENT_SUPPLY: u32 = 8;
+
+fn program_id() -> Pubkey {
+    Pubkey::new_from_array([2; 32])
+}
+
+fn balance_pda(user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&user.to_bytes()], &program_id())
+}
+
+fn allowance_pda(user: &Pubkey, delegate: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[&user.to_bytes(), &delegate.to_bytes()], &program_id()).0
+}
+
+fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[demo2::CONFIG_SEED], &program_id())
+}
+
+fn system_account() -> Account {
+    Account { lamports: USER_LAMPORTS, data: vec![], owner: system_program::ID, executable: false, rent_epoch: 0 }
+}
+
+fn program_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn balance_data(user: &Pubkey, bump: u8, amount: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(demo2::BALANCE_LEN);
+    data.push(demo2::BALANCE_TAG);
+    data.push(demo2::BALANCE_VERSION);
+    data.extend_from_slice(user.as_ref());
+    data.push(bump);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+fn program_test(users: &[&Pubkey]) -> ProgramTest {
+    let mut program_test = ProgramTest::new("demo2", program_id(), processor!(demo2::process_instruction));
+    for user in users {
+        program_test.add_account(**user, system_account());
+    }
+    program_test
+}
+
+// ADMIN 的私钥不在测试里，所以直接放入一个已初始化的 config，mint authority 由测试指定
+fn add_config(program_test: &mut ProgramTest, authority: &Pubkey, supply: u64) {
+    let (config, bump) = config_pda();
+    let mut data = Vec::with_capacity(demo2::CONFIG_LEN);
+    data.extend_from_slice(authority.as_ref());
+    data.extend_from_slice(&supply.to_le_bytes());
+    data.push(bump);
+    program_test.add_account(config, program_account(data));
+}
+
+// 旧版本（version 0）的余额账户：裸的大端序 u64
+fn add_balance_v0(program_test: &mut ProgramTest, user: &Pubkey, amount: u64) {
+    program_test.add_account(balance_pda(user).0, program_account(amount.to_be_bytes().to_vec()));
+}
+
+// 指令里的金额是大端序 u64，和账户里存的小端序不同
+fn instruction(tag: u8, amount: Option<u64>, accounts: Vec<AccountMeta>) -> Instruction {
+    let mut data = vec![tag];
+    if let Some(amount) = amount {
+        data.extend_from_slice(&amount.to_be_bytes());
+    }
+    Instruction { program_id: program_id(), accounts, data }
+}
+
+fn mint_instruction(user: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(balance_pda(user).0, false),
+        AccountMeta::new(config_pda().0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(sysvar::rent::ID, false),
+    ];
+    instruction(MINT, Some(amount), accounts)
+}
+
+fn transfer_instruction(user: &Pubkey, into: &Pubkey, amount: u64) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new(balance_pda(user).0, false),
+        AccountMeta::new_readonly(*into, false),
+        AccountMeta::new(balance_pda(into).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(sysvar::rent::ID, false),
+    ];
+    instruction(TRANSFER, Some(amount), accounts)
+}
+
+fn migrate_instruction(payer: &Pubkey, user: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*user, false),
+        AccountMeta::new(balance_pda(user).0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    instruction(MIGRATE, None, accounts)
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn balance_of(context: &mut ProgramTestContext, user: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(balance_pda(user).0).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), demo2::BALANCE_LEN);
+    u64::from_le_bytes(account.data[35..43].try_into().unwrap())
+}
+
+async fn supply(context: &mut ProgramTestContext) -> u64 {
+    let account = context.banks_client.get_account(config_pda().0).await.unwrap().unwrap();
+    u64::from_le_bytes(account.data[32..40].try_into().unwrap())
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(result.unwrap_err().unwrap(), TransactionError::InstructionError(0, expected));
+}
+
+#[tokio::test]
+async fn test_initialize_by_non_admin_is_rejected() {
+    let user = Keypair::new();
+    let mut context = program_test(&[&user.pubkey()]).start_with_context().await;
+
+    let accounts = vec![
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new(config_pda().0, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    let result = process(&mut context, instruction(INITIALIZE, None, accounts), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn test_mint_transfer_and_burn() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_config(&mut program_test, &user.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+
+    process(&mut context, mint_instruction(&user.pubkey(), 100), &[&user]).await.unwrap();
+    process(&mut context, transfer_instruction(&user.pubkey(), &into, 30), &[&user]).await.unwrap();
+    let accounts = vec![
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new(balance_pda(&user.pubkey()).0, false),
+        AccountMeta::new(config_pda().0, false),
+    ];
+    process(&mut context, instruction(BURN, Some(20), accounts), &[&user]).await.unwrap();
+
+    assert_eq!(balance_of(&mut context, &user.pubkey()).await, 50);
+    assert_eq!(balance_of(&mut context, &into).await, 30);
+    assert_eq!(supply(&mut context).await, 80);
+    let account = context.banks_client.get_account(balance_pda(&into).0).await.unwrap().unwrap();
+    assert_eq!(account.data, balance_data(&into, balance_pda(&into).1, 30));
+}
+
+#[tokio::test]
+async fn test_burn_more_than_supply_is_rejected() {
+    let user = Keypair::new();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    // 余额比总供应量多，例如在记录供应量之前就存在的余额
+    add_config(&mut program_test, &user.pubkey(), 10);
+    let (user_pda, bump) = balance_pda(&user.pubkey());
+    program_test.add_account(user_pda, program_account(balance_data(&user.pubkey(), bump, 50)));
+    let mut context = program_test.start_with_context().await;
+
+    let accounts = vec![
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new(user_pda, false),
+        AccountMeta::new(config_pda().0, false),
+    ];
+    let result = process(&mut context, instruction(BURN, Some(20), accounts), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(INSUFFICIENT_SUPPLY));
+    assert_eq!(balance_of(&mut context, &user.pubkey()).await, 50);
+    assert_eq!(supply(&mut context).await, 10);
+}
+
+#[tokio::test]
+async fn test_mint_by_non_authority_is_rejected() {
+    let user = Keypair::new();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_config(&mut program_test, &Keypair::new().pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process(&mut context, mint_instruction(&user.pubkey(), 100), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(UNAUTHORIZED));
+}
+
+#[tokio::test]
+async fn test_transfer_more_than_balance_is_rejected() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_config(&mut program_test, &user.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+
+    process(&mut context, mint_instruction(&user.pubkey(), 10), &[&user]).await.unwrap();
+    let result = process(&mut context, transfer_instruction(&user.pubkey(), &into, 11), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(INSUFFICIENT_BALANCE));
+}
+
+#[tokio::test]
+async fn test_approve_and_transfer_from() {
+    let user = Keypair::new();
+    let delegate = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey(), &delegate.pubkey()]);
+    add_config(&mut program_test, &user.pubkey(), 0);
+    let mut context = program_test.start_with_context().await;
+    let allowance = allowance_pda(&user.pubkey(), &delegate.pubkey());
+
+    process(&mut context, mint_instruction(&user.pubkey(), 100), &[&user]).await.unwrap();
+    let accounts = vec![
+        AccountMeta::new(user.pubkey(), true),
+        AccountMeta::new_readonly(delegate.pubkey(), false),
+        AccountMeta::new(allowance, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(sysvar::rent::ID, false),
+    ];
+    process(&mut context, instruction(APPROVE, Some(40), accounts), &[&user]).await.unwrap();
+
+    let transfer_from = |amount: u64| {
+        let accounts = vec![
+            AccountMeta::new(delegate.pubkey(), true),
+            AccountMeta::new_readonly(user.pubkey(), false),
+            AccountMeta::new(balance_pda(&user.pubkey()).0, false),
+            AccountMeta::new_readonly(into, false),
+            AccountMeta::new(balance_pda(&into).0, false),
+            AccountMeta::new(allowance, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ];
+        instruction(TRANSFER_FROM, Some(amount), accounts)
+    };
+    process(&mut context, transfer_from(25), &[&delegate]).await.unwrap();
+    let result = process(&mut context, transfer_from(16), &[&delegate]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(INSUFFICIENT_ALLOWANCE));
+    assert_eq!(balance_of(&mut context, &user.pubkey()).await, 75);
+    assert_eq!(balance_of(&mut context, &into).await, 25);
+    let account = context.banks_client.get_account(allowance).await.unwrap().unwrap();
+    assert_eq!(account.data, 15u64.to_le_bytes());
+}
+
+#[tokio::test]
+async fn test_outdated_balance_is_rejected() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_balance_v0(&mut program_test, &user.pubkey(), 100);
+    let mut context = program_test.start_with_context().await;
+
+    let result = process(&mut context, transfer_instruction(&user.pubkey(), &into, 1), &[&user]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(OUTDATED_BALANCE_ACCOUNT));
+}
+
+#[tokio::test]
+async fn test_migrate_keeps_balance() {
+    let user = Keypair::new();
+    let into = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&user.pubkey()]);
+    add_balance_v0(&mut program_test, &user.pubkey(), 0x0102_0304);
+    let mut context = program_test.start_with_context().await;
+
+    // 任何人都可以替别人付费迁移
+    let payer = context.payer.pubkey();
+    process(&mut context, migrate_instruction(&payer, &user.pubkey()), &[]).await.unwrap();
+
+    let (user_pda, bump) = balance_pda(&user.pubkey());
+    let account = context.banks_client.get_account(user_pda).await.unwrap().unwrap();
+    assert_eq!(account.data, balance_data(&user.pubkey(), bump, 0x0102_0304));
+    assert_eq!(account.lamports, Rent::default().minimum_balance(demo2::BALANCE_LEN));
+
+    // 迁移后的账户可以正常转账
+    process(&mut context, transfer_instruction(&user.pubkey(), &into, 4), &[&user]).await.unwrap();
+    assert_eq!(balance_of(&mut context, &user.pubkey()).await, 0x0102_0300);
+}
+
+#[tokio::test]
+async fn test_migrate_twice_is_noop() {
+    let user = Keypair::new().pubkey();
+    let mut program_test = program_test(&[]);
+    add_balance_v0(&mut program_test, &user, 7);
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    process(&mut context, migrate_instruction(&payer, &user), &[]).await.unwrap();
+    let before = context.banks_client.get_account(balance_pda(&user).0).await.unwrap().unwrap();
+    // 交易内容相同会被当作重复交易，换一个 blockhash
+    context.last_blockhash = context.banks_client.get_new_latest_blockhash(&context.last_blockhash).await.unwrap();
+    process(&mut context, migrate_instruction(&payer, &user), &[]).await.unwrap();
+    let after = context.banks_client.get_account(balance_pda(&user).0).await.unwrap().unwrap();
+
+    assert_eq!(before, after);
+}
+
+#[tokio::test]
+async fn test_migrate_wrong_pda_is_rejected() {
+    let user = Keypair::new().pubkey();
+    let other = Keypair::new().pubkey();
+    let mut program_test = program_test(&[]);
+    add_balance_v0(&mut program_test, &other, 7);
+    let mut context = program_test.start_with_context().await;
+
+    let payer = context.payer.pubkey();
+    let mut migrate = migrate_instruction(&payer, &user);
+    migrate.accounts[2] = AccountMeta::new(balance_pda(&other).0, false);
+    let result = process(&mut context, migrate, &[]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+}
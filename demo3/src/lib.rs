@@ -0,0 +1,383 @@
+#![allow(unexpected_cfgs)]
+
+// 引入Anchor，以及SPL Token代币模块
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+
+// 声明程序地址，可不填，编译时帮填
+declare_id!("HJUWSUMgK2bDaY7Ve4xQZ46tcgfJQFSRWBMK1wTCaz6Z");
+
+// Anchor的程序宏
+#[program]
+// 定义具体的模块
+pub mod token_vault {
+
+    // 导入当前模块的父模块的全部公开项（变量 、函数等）
+    use super::*;
+
+    // 初始化相关的账户信息：为某个 mint 创建存储令牌的账户和份额令牌的 mint
+    pub fn initialize(_ctx: Context<Initialize>) -> Result<()> {
+        Ok(())
+    }
+
+    // 令牌转入 函数：存入底层令牌，按比例铸造份额令牌
+    pub fn transfer_in(ctx: Context<TransferIn>, amount: u64) -> Result<()> {
+        msg!("Token amount transfer in: {}!", amount);
+        require_gt!(amount, 0, VaultError::ZeroAmount);
+
+        // 按存入前的金库余额计算份额
+        let shares = shares_for_deposit(
+            amount,
+            ctx.accounts.vault_token_account.amount,
+            ctx.accounts.share_mint.supply,
+        )?;
+        require_gt!(shares, 0, VaultError::ZeroShares);
+
+        // 构建转移令牌的指令
+        let transfer_instruction = Transfer {
+            // 个人账户的令牌ATA账户
+            from: ctx.accounts.sender_token_account.to_account_info(),
+            // 存储令牌的新账户
+            to: ctx.accounts.vault_token_account.to_account_info(),
+            // 签名者
+            authority: ctx.accounts.signer.to_account_info(),
+        };
+
+        // 构建 跨程序调用指令（CPI）
+        let cpi_ctx = CpiContext::new(
+            // 调用目标是 SPL令牌程序
+            ctx.accounts.token_program.to_account_info(),
+            // 转移令牌指令
+            transfer_instruction,
+
+            // 因为转入的操作是由用户直接发起的，用户已经签名了整个交易
+            // 由用户的 ATA 转入 存储令牌 的新账户，所以这里不需要再签名
+        );
+
+        // 执行转移指令
+        anchor_spl::token::transfer(cpi_ctx, amount)?;
+
+        // 份额 mint 的授权者是 管理PDA，需要 PDA 签名铸造
+        let bump = ctx.bumps.token_account_owner_pda;
+        let seeds = &[b"token_account_owner_pda".as_ref(), &[bump]];
+        let signer = &[&seeds[..]];
+
+        let mint_instruction = MintTo {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            to: ctx.accounts.user_share_account.to_account_info(),
+            authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_instruction,
+            signer,
+        );
+        anchor_spl::token::mint_to(cpi_ctx, shares)?;
+
+        Ok(())
+    }
+
+    // 令牌转出 函数：销毁份额令牌，按比例取回底层令牌
+    pub fn transfer_out(ctx: Context<TransferOut>, shares: u64) -> Result<()> {
+        msg!("Share amount transfer out: {}!", shares);
+        require_gt!(shares, 0, VaultError::ZeroAmount);
+
+        // 按销毁前的金库余额和份额总量计算可取回的数量
+        let amount = amount_for_shares(
+            shares,
+            ctx.accounts.vault_token_account.amount,
+            ctx.accounts.share_mint.supply,
+        )?;
+        require_gt!(amount, 0, VaultError::ZeroAmount);
+
+        // 用户销毁自己的份额，余额不足时由 SPL Token 程序拒绝
+        let burn_instruction = Burn {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            from: ctx.accounts.user_share_account.to_account_info(),
+            authority: ctx.accounts.signer.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), burn_instruction);
+        anchor_spl::token::burn(cpi_ctx, shares)?;
+
+        // 构建转移令牌指令
+        let transfer_instruction = Transfer {
+            from: ctx.accounts.vault_token_account.to_account_info(),
+            to: ctx.accounts.sender_token_account.to_account_info(),
+            authority: ctx.accounts.token_account_owner_pda.to_account_info(),
+        };
+
+        // 获取与 token_account_owner_pda PDA账户相关的 bump随机数
+        let bump = ctx.bumps.token_account_owner_pda;
+        // 生成 token_account_owner_pda PDA账户的 种子
+        let seeds = &[b"token_account_owner_pda".as_ref(), &[bump]];
+        // 创建签名者数组，seeds 来签名
+        let signer = &[&seeds[..]];
+
+        // 构建 跨程序调用（CPI）
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_instruction,
+
+            // 这里是由 存储令牌的新账户，转出至 用户的 ATA 账户
+            // 控制 存储令牌的新账户 是 管理PDA，所以这里需要添加一个签名者（管理 PDA）
+            signer,
+        );
+
+        anchor_spl::token::transfer(cpi_ctx, amount)?;
+
+        Ok(())
+    }
+}
+
+// 份额换算：分子分母各加 1 个虚拟份额/令牌，
+// 空金库时 1 令牌 = 1 份额，也让直接向金库捐赠令牌来稀释后来者的攻击变得无利可图
+pub fn shares_for_deposit(amount: u64, vault_balance: u64, share_supply: u64) -> Result<u64> {
+    let shares = (amount as u128)
+        .checked_mul(share_supply as u128 + 1)
+        .ok_or(VaultError::Overflow)?
+        / (vault_balance as u128 + 1);
+    u64::try_from(shares).map_err(|_| error!(VaultError::Overflow))
+}
+
+pub fn amount_for_shares(shares: u64, vault_balance: u64, share_supply: u64) -> Result<u64> {
+    let amount = (shares as u128)
+        .checked_mul(vault_balance as u128 + 1)
+        .ok_or(VaultError::Overflow)?
+        / (share_supply as u128 + 1);
+    u64::try_from(amount).map_err(|_| error!(VaultError::Overflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_deposit_mints_one_share_per_token() {
+        assert_eq!(shares_for_deposit(1_000, 0, 0).unwrap(), 1_000);
+        assert_eq!(amount_for_shares(1_000, 1_000, 1_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_small_deposit_rounds_to_zero_shares() {
+        // 1 份额对应 91 个令牌，少于 91 个令牌换不到份额，transfer_in 会以 ZeroShares 拒绝
+        assert_eq!(shares_for_deposit(50, 1_000, 10).unwrap(), 0);
+        assert_eq!(shares_for_deposit(90, 1_000, 10).unwrap(), 0);
+        assert_eq!(shares_for_deposit(91, 1_000, 10).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_donation_attack_is_unprofitable() {
+        // 攻击者先存 1 个令牌拿到 1 份额，再直接向金库捐赠，让受害者的存款四舍五入丢掉一部分
+        let attacker_shares = shares_for_deposit(1, 0, 0).unwrap();
+        assert_eq!(attacker_shares, 1);
+        let donation = 1_000_000;
+        let (mut balance, mut supply) = (1 + donation, attacker_shares);
+
+        let deposit = 1_000_000;
+        let victim_shares = shares_for_deposit(deposit, balance, supply).unwrap();
+        assert_eq!(victim_shares, 1);
+        balance += deposit;
+        supply += victim_shares;
+
+        let attacker_out = amount_for_shares(attacker_shares, balance, supply).unwrap();
+        balance -= attacker_out;
+        supply -= attacker_shares;
+        let victim_out = amount_for_shares(victim_shares, balance, supply).unwrap();
+
+        // 虚拟份额吃掉了捐赠的一部分：攻击者亏的和受害者差不多，攻击无利可图
+        let attacker_loss = 1 + donation - attacker_out;
+        let victim_loss = deposit - victim_out;
+        assert_eq!(attacker_loss, 333_334);
+        assert_eq!(victim_loss, 333_333);
+    }
+
+    #[test]
+    fn test_redeem_all_shares() {
+        // 没有收益时全部取回
+        let shares = shares_for_deposit(1_000, 0, 0).unwrap();
+        assert_eq!(amount_for_shares(shares, 1_000, shares).unwrap(), 1_000);
+
+        // 有收益时最多取走金库余额，虚拟份额只留下不到 1 个令牌的舍入
+        let amount = amount_for_shares(shares, 1_500, shares).unwrap();
+        assert!(amount <= 1_500);
+        assert_eq!(amount, 1_499);
+    }
+
+    #[test]
+    fn test_overflow_is_rejected() {
+        assert!(shares_for_deposit(u64::MAX, 0, u64::MAX).is_err());
+        assert!(amount_for_shares(u64::MAX, u64::MAX, 0).is_err());
+    }
+}
+
+#[error_code]
+pub enum VaultError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Deposit is too small to mint any shares")]
+    ZeroShares,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+
+    /// CHECK: 管理PDA，只作为 vault_token_account 的授权者，不保存数据，无需创建
+    // 因为是PDA账户，合约在需要时可以直接通过种子短语签名，无需私钥
+    #[account(
+        seeds=[b"token_account_owner_pda"],
+        bump
+    )]
+    pub token_account_owner_pda: UncheckedAccount<'info>,
+
+    // 存储令牌的新账户，并由 token_account_owner_pda 控制
+    #[account(
+        init_if_needed,
+        payer = signer,
+        // 种子 包含"token_vault"，以及mint_of_token_being_sent的公钥
+        seeds=[b"token_vault", mint_of_token_being_sent.key().as_ref()],
+        // 指定存储的令牌
+        token::mint=mint_of_token_being_sent,
+        // 指定该账户的所有者，即token_account_owner_pda
+        // 不主动声明的话，默认为创建它的智能合约
+        token::authority=token_account_owner_pda,
+        bump
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    // 份额令牌的 mint，每个底层 mint 一个，由 token_account_owner_pda 铸造
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds=[b"share_mint", mint_of_token_being_sent.key().as_ref()],
+        bump,
+        mint::decimals=mint_of_token_being_sent.decimals,
+        mint::authority=token_account_owner_pda,
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    // 令牌的mint地址
+    pub mint_of_token_being_sent: Account<'info, Mint>,
+
+    // 签名者
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // Solana 系统账户，自动生成
+    pub system_program: Program<'info, System>,
+
+    // SPL Token程序账户，自动生成
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct TransferIn<'info> {
+
+    /// CHECK: 管理PDA，vault_token_account 的授权者，同时为铸造份额签名
+    #[account(
+        seeds=[b"token_account_owner_pda"],
+        bump
+    )]
+    pub token_account_owner_pda: UncheckedAccount<'info>,
+
+    // 存储令牌的新地址
+    #[account(mut,
+        seeds=[b"token_vault", mint_of_token_being_sent.key().as_ref()],
+        bump,
+        token::mint=mint_of_token_being_sent,
+        token::authority=token_account_owner_pda,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    // 份额令牌的 mint
+    #[account(mut,
+        seeds=[b"share_mint", mint_of_token_being_sent.key().as_ref()],
+        bump,
+        mint::authority=token_account_owner_pda,
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    // 用户接收份额的 ATA，不存在时自动创建
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint=share_mint,
+        associated_token::authority=signer,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    // 发送者的令牌账户ATA
+    #[account(mut,
+        token::mint=mint_of_token_being_sent,
+        token::authority=signer,
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+
+    // 代币的mint地址
+    pub mint_of_token_being_sent: Account<'info, Mint>,
+
+    // 签名者
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // 系统账户
+    pub system_program: Program<'info, System>,
+
+    // SPL 令牌程序
+    pub token_program: Program<'info, Token>,
+
+    // ATA 程序，用于创建 user_share_account
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+#[derive(Accounts)]
+pub struct TransferOut<'info> {
+
+    /// CHECK: 管理PDA，作为 vault_token_account 的授权者为转出签名
+    #[account(
+        seeds=[b"token_account_owner_pda"],
+        bump
+    )]
+    pub token_account_owner_pda: UncheckedAccount<'info>,
+
+    // 存储令牌的新地址
+    #[account(mut,
+        seeds=[b"token_vault", mint_of_token_being_sent.key().as_ref()],
+        bump,
+        token::mint=mint_of_token_being_sent,
+        token::authority=token_account_owner_pda,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    // 份额令牌的 mint
+    #[account(mut,
+        seeds=[b"share_mint", mint_of_token_being_sent.key().as_ref()],
+        bump,
+    )]
+    pub share_mint: Account<'info, Mint>,
+
+    // 用户持有份额的账户，签名者销毁其中的份额
+    #[account(mut,
+        token::mint=share_mint,
+        token::authority=signer,
+    )]
+    pub user_share_account: Account<'info, TokenAccount>,
+
+    // 接收令牌的账户
+    #[account(mut,
+        token::mint=mint_of_token_being_sent,
+    )]
+    pub sender_token_account: Account<'info, TokenAccount>,
+
+    // 代币的mint地址
+    pub mint_of_token_being_sent: Account<'info, Mint>,
+
+    // 签名者，即份额持有人
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    // SPL 令牌程序
+    pub token_program: Program<'info, Token>,
+}
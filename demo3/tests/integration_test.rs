@@ -0,0 +1,227 @@
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+// 程序通过 target/deploy/token_vault.so 加载，SPL Token 和 ATA 程序由 solana-program-test 自带。
+use anchor_lang::solana_program::{program_pack::Pack, system_instruction, system_program};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{get_associated_token_address, spl_associated_token_account};
+use anchor_spl::token::{spl_token, Mint, TokenAccount};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::{Transaction, TransactionError},
+};
+
+const USER_TOKENS: u64 = 1_000_000;
+
+// VaultError，Anchor 的自定义错误从 6000 开始
+const ZERO_AMOUNT: u32 = 6000;
+const ZERO_SHARES: u32 = 6001;
+
+struct Vault {
+    mint: Pubkey,
+    owner_pda: Pubkey,
+    vault_token_account: Pubkey,
+    share_mint: Pubkey,
+    user_token_account: Pubkey,
+    user_share_account: Pubkey,
+}
+
+impl Vault {
+    fn new(mint: Pubkey, user: &Pubkey) -> Self {
+        let find = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &token_vault::ID).0;
+        let share_mint = find(&[b"share_mint", mint.as_ref()]);
+        Vault {
+            mint,
+            owner_pda: find(&[b"token_account_owner_pda"]),
+            vault_token_account: find(&[b"token_vault", mint.as_ref()]),
+            share_mint,
+            user_token_account: get_associated_token_address(user, &mint),
+            user_share_account: get_associated_token_address(user, &share_mint),
+        }
+    }
+
+    fn initialize(&self, user: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: token_vault::ID,
+            accounts: token_vault::accounts::Initialize {
+                token_account_owner_pda: self.owner_pda,
+                vault_token_account: self.vault_token_account,
+                share_mint: self.share_mint,
+                mint_of_token_being_sent: self.mint,
+                signer: *user,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+            }
+            .to_account_metas(None),
+            data: token_vault::instruction::Initialize {}.data(),
+        }
+    }
+
+    fn transfer_in(&self, user: &Pubkey, amount: u64) -> Instruction {
+        Instruction {
+            program_id: token_vault::ID,
+            accounts: token_vault::accounts::TransferIn {
+                token_account_owner_pda: self.owner_pda,
+                vault_token_account: self.vault_token_account,
+                share_mint: self.share_mint,
+                user_share_account: self.user_share_account,
+                sender_token_account: self.user_token_account,
+                mint_of_token_being_sent: self.mint,
+                signer: *user,
+                system_program: system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            }
+            .to_account_metas(None),
+            data: token_vault::instruction::TransferIn { amount }.data(),
+        }
+    }
+
+    fn transfer_out(&self, user: &Pubkey, shares: u64) -> Instruction {
+        Instruction {
+            program_id: token_vault::ID,
+            accounts: token_vault::accounts::TransferOut {
+                token_account_owner_pda: self.owner_pda,
+                vault_token_account: self.vault_token_account,
+                share_mint: self.share_mint,
+                user_share_account: self.user_share_account,
+                sender_token_account: self.user_token_account,
+                mint_of_token_being_sent: self.mint,
+                signer: *user,
+                token_program: spl_token::ID,
+            }
+            .to_account_metas(None),
+            data: token_vault::instruction::TransferOut { shares }.data(),
+        }
+    }
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+// 创建底层令牌的 mint，给 payer 的 ATA 铸造 USER_TOKENS，再初始化金库
+async fn setup() -> (ProgramTestContext, Vault) {
+    let mut program_test = ProgramTest::new("token_vault", token_vault::ID, None);
+    program_test.prefer_bpf(true);
+    let mut context = program_test.start_with_context().await;
+
+    let user = context.payer.pubkey();
+    let mint = Keypair::new();
+    let vault = Vault::new(mint.pubkey(), &user);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        system_instruction::create_account(
+            &user,
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::ID,
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &user, None, 6).unwrap(),
+        spl_associated_token_account::instruction::create_associated_token_account(
+            &user,
+            &user,
+            &mint.pubkey(),
+            &spl_token::ID,
+        ),
+        spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &mint.pubkey(),
+            &vault.user_token_account,
+            &user,
+            &[],
+            USER_TOKENS,
+        )
+        .unwrap(),
+    ];
+    process(&mut context, &instructions, &[&mint]).await.unwrap();
+    process(&mut context, &[vault.initialize(&user)], &[]).await.unwrap();
+
+    (context, vault)
+}
+
+async fn token_balance(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    let account = context.banks_client.get_account(*address).await.unwrap().unwrap();
+    TokenAccount::try_deserialize(&mut &account.data[..]).unwrap().amount
+}
+
+async fn share_supply(context: &mut ProgramTestContext, vault: &Vault) -> u64 {
+    let account = context.banks_client.get_account(vault.share_mint).await.unwrap().unwrap();
+    Mint::try_deserialize(&mut &account.data[..]).unwrap().supply
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(result.unwrap_err().unwrap(), TransactionError::InstructionError(0, expected));
+}
+
+#[tokio::test]
+async fn test_deposit_and_withdraw_round_trip() {
+    let (mut context, vault) = setup().await;
+    let user = context.payer.pubkey();
+
+    // 空金库时 1 令牌 = 1 份额
+    process(&mut context, &[vault.transfer_in(&user, 400_000)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &vault.vault_token_account).await, 400_000);
+    assert_eq!(token_balance(&mut context, &vault.user_share_account).await, 400_000);
+    assert_eq!(token_balance(&mut context, &vault.user_token_account).await, USER_TOKENS - 400_000);
+
+    process(&mut context, &[vault.transfer_out(&user, 150_000)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &vault.user_share_account).await, 250_000);
+
+    // 销毁全部份额，令牌全部取回
+    process(&mut context, &[vault.transfer_out(&user, 250_000)], &[]).await.unwrap();
+    assert_eq!(token_balance(&mut context, &vault.vault_token_account).await, 0);
+    assert_eq!(token_balance(&mut context, &vault.user_share_account).await, 0);
+    assert_eq!(token_balance(&mut context, &vault.user_token_account).await, USER_TOKENS);
+    assert_eq!(share_supply(&mut context, &vault).await, 0);
+}
+
+#[tokio::test]
+async fn test_zero_amount_is_rejected() {
+    let (mut context, vault) = setup().await;
+    let user = context.payer.pubkey();
+
+    let result = process(&mut context, &[vault.transfer_in(&user, 0)], &[]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(ZERO_AMOUNT));
+}
+
+#[tokio::test]
+async fn test_deposit_rounding_to_zero_shares_is_rejected() {
+    let (mut context, vault) = setup().await;
+    let user = context.payer.pubkey();
+
+    process(&mut context, &[vault.transfer_in(&user, 10)], &[]).await.unwrap();
+    // 直接向金库捐赠令牌，不铸造份额：之后 1 份额约等于 91 个令牌
+    let donate = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &vault.user_token_account,
+        &vault.vault_token_account,
+        &user,
+        &[],
+        990,
+    )
+    .unwrap();
+    process(&mut context, &[donate], &[]).await.unwrap();
+
+    let result = process(&mut context, &[vault.transfer_in(&user, 50)], &[]).await;
+
+    assert_instruction_error(result, InstructionError::Custom(ZERO_SHARES));
+    assert_eq!(token_balance(&mut context, &vault.vault_token_account).await, 1_000);
+    assert_eq!(share_supply(&mut context, &vault).await, 10);
+}
@@ -36,14 +36,17 @@ impl<'info> TryFrom<(&'info [AccountView], &'info [u8])> for VaultContext<'info>
         return Err(ProgramError::InvalidAccountData);
      }
      // 处理指令数据长度不足的情况
-     let lamports = if value.1.len() == 8 {
+     let lamports = match value.1.len() {
          // 正常情况：8 字节金额数据
-         let mut bytes = [0u8; 8];
-         bytes.copy_from_slice(value.1);
-         u64::from_le_bytes(bytes)
-     } else {
+         8 => {
+             let mut bytes = [0u8; 8];
+             bytes.copy_from_slice(value.1);
+             u64::from_le_bytes(bytes)
+         }
          // 没有提供金额数据：提取所有余额
-         vault.lamports()
+         0 => vault.lamports(),
+         // 金额数据被截断
+         _ => return Err(ProgramError::InvalidInstructionData),
      };
      Ok(Self { owner, vault, lamports,bump })
    }
@@ -25,7 +25,7 @@ echo "Vault 地址: $VAULT_ADDRESS"
 
 # 部署程序
 echo "部署程序..."
-solana program deploy target/deploy/blueshift_vault.so
+solana program deploy target/deploy/first_pinocchio.so
 
 # 测试 withdraw 指令
 echo "测试 withdraw 指令..."
@@ -1,138 +1,296 @@
-use blueshift_vault::process_instruction;
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+// 程序通过 target/deploy/first_pinocchio.so 加载，使用 first_pinocchio 自己的 entrypoint。
 use solana_program_test::*;
-use solana_sdk::{signature::Keypair, signer::Signer, transaction::Transaction, pubkey::Pubkey};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
 use std::str::FromStr;
 
-#[tokio::test]
-async fn test_deposit_instruction() {
-    // Setup test environment
-    let program_id = Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap();
-    let owner = Keypair::new();
-    let vault = Keypair::new();
-    
-    // Create test accounts
-    let mut program_test = ProgramTest::new(
-        "blueshift_vault",
-        program_id,
-        processor!(process_instruction),
-    );
-    
-    // Add accounts to test environment
-    program_test.add_account(
-        owner.pubkey(),
-        solana_sdk::account::Account {
-            lamports: 1000000,
-            data: vec![],
-            owner: solana_sdk::system_program::ID,
-            executable: false,
-            rent_epoch: 0,
-        },
-    );
-    
+const DEPOSIT: u8 = 0;
+const WITHDRAW: u8 = 1;
+const OWNER_LAMPORTS: u64 = 10_000_000_000;
+
+fn program_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn vault_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id()).0
+}
+
+fn program_test(owner: &Pubkey, vault_lamports: u64) -> ProgramTest {
+    // processor 传 None：加载 target/deploy 下的 SBF 程序
+    let mut program_test = ProgramTest::new("first_pinocchio", program_id(), None);
+    program_test.prefer_bpf(true);
+
     program_test.add_account(
-        vault.pubkey(),
-        solana_sdk::account::Account {
-            lamports: 0,
+        *owner,
+        Account {
+            lamports: OWNER_LAMPORTS,
             data: vec![],
-            owner: solana_sdk::system_program::ID,
+            owner: system_program::ID,
             executable: false,
             rent_epoch: 0,
         },
     );
-    
-    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
-    
-    // Test deposit instruction
-    let deposit_amount: u64 = 100000;
-    let mut instruction_data = vec![0]; // Discriminator for deposit
-    instruction_data.extend_from_slice(&deposit_amount.to_le_bytes());
-    
-    let instruction = solana_sdk::instruction::Instruction {
-        program_id: program_id,
+
+    if vault_lamports > 0 {
+        // vault 是 [b"vault", owner] PDA，由 System Program 持有
+        program_test.add_account(
+            vault_pda(owner),
+            Account {
+                lamports: vault_lamports,
+                data: vec![],
+                owner: system_program::ID,
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+    }
+
+    program_test
+}
+
+fn vault_instruction(owner: &Pubkey, vault: &Pubkey, is_signer: bool, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: program_id(),
         accounts: vec![
-            solana_sdk::instruction::AccountMeta::new(owner.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(vault.pubkey(), false),
-            solana_sdk::instruction::AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
+            AccountMeta::new(*owner, is_signer),
+            AccountMeta::new(*vault, false),
+            AccountMeta::new_readonly(system_program::ID, false),
         ],
-        data: instruction_data,
-    };
-    
+        data,
+    }
+}
+
+fn instruction_data(discriminator: u8, amount: Option<u64>) -> Vec<u8> {
+    let mut data = vec![discriminator];
+    if let Some(amount) = amount {
+        data.extend_from_slice(&amount.to_le_bytes());
+    }
+    data
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
     let transaction = Transaction::new_signed_with_payer(
         &[instruction],
-        Some(&payer.pubkey()),
-        &[&payer, &owner],
-        recent_blockhash,
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn lamports(context: &mut ProgramTestContext, address: &Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .map(|account| account.lamports)
+        .unwrap_or(0)
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, expected)
     );
-    
-    // Send transaction and check result
-    let result = banks_client.process_transaction(transaction).await;
-    println!("Deposit transaction result: {:?}", result);
-    assert!(result.is_ok());
 }
 
 #[tokio::test]
-async fn test_withdraw_instruction() {
-    // Setup test environment
-    let program_id = Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap();
+async fn test_deposit() {
     let owner = Keypair::new();
-    let vault = Keypair::new();
-    
-    // Create test accounts
-    let mut program_test = ProgramTest::new(
-        "blueshift_vault",
-        program_id,
-        processor!(process_instruction),
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 0).start_with_context().await;
+
+    let amount = Rent::default().minimum_balance(0) + 1_000_000;
+    let instruction = vault_instruction(
+        &owner.pubkey(),
+        &vault,
+        true,
+        instruction_data(DEPOSIT, Some(amount)),
     );
-    
-    // Add accounts to test environment
-    program_test.add_account(
-        owner.pubkey(),
-        solana_sdk::account::Account {
-            lamports: 500000,
-            data: vec![],
-            owner: solana_sdk::system_program::ID,
-            executable: false,
-            rent_epoch: 0,
-        },
+    process(&mut context, instruction, &[&owner]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &vault).await, amount);
+    assert_eq!(lamports(&mut context, &owner.pubkey()).await, OWNER_LAMPORTS - amount);
+}
+
+#[tokio::test]
+async fn test_withdraw() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let vault_lamports = 1_000_000_000;
+    let mut context = program_test(&owner.pubkey(), vault_lamports)
+        .start_with_context()
+        .await;
+
+    let amount = 400_000_000;
+    let instruction = vault_instruction(
+        &owner.pubkey(),
+        &vault,
+        true,
+        instruction_data(WITHDRAW, Some(amount)),
+    );
+    process(&mut context, instruction, &[&owner]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &vault).await, vault_lamports - amount);
+    assert_eq!(lamports(&mut context, &owner.pubkey()).await, OWNER_LAMPORTS + amount);
+}
+
+#[tokio::test]
+async fn test_withdraw_without_amount_withdraws_all() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let vault_lamports = 1_000_000_000;
+    let mut context = program_test(&owner.pubkey(), vault_lamports)
+        .start_with_context()
+        .await;
+
+    let instruction = vault_instruction(&owner.pubkey(), &vault, true, instruction_data(WITHDRAW, None));
+    process(&mut context, instruction, &[&owner]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &vault).await, 0);
+    assert_eq!(
+        lamports(&mut context, &owner.pubkey()).await,
+        OWNER_LAMPORTS + vault_lamports
+    );
+}
+
+#[tokio::test]
+async fn test_deposit_then_withdraw_all() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 0).start_with_context().await;
+
+    let amount = 2_000_000_000;
+    let deposit = vault_instruction(
+        &owner.pubkey(),
+        &vault,
+        true,
+        instruction_data(DEPOSIT, Some(amount)),
+    );
+    process(&mut context, deposit, &[&owner]).await.unwrap();
+
+    let withdraw = vault_instruction(&owner.pubkey(), &vault, true, instruction_data(WITHDRAW, None));
+    process(&mut context, withdraw, &[&owner]).await.unwrap();
+
+    assert_eq!(lamports(&mut context, &vault).await, 0);
+    assert_eq!(lamports(&mut context, &owner.pubkey()).await, OWNER_LAMPORTS);
+}
+
+#[tokio::test]
+async fn test_wrong_pda_is_rejected() {
+    let owner = Keypair::new();
+    let mut context = program_test(&owner.pubkey(), 0).start_with_context().await;
+
+    // 一个普通地址而不是 [b"vault", owner] PDA
+    let not_vault = Keypair::new().pubkey();
+    let instruction = vault_instruction(
+        &owner.pubkey(),
+        &not_vault,
+        true,
+        instruction_data(DEPOSIT, Some(1_000_000_000)),
     );
-    
+    let result = process(&mut context, instruction, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+}
+
+#[tokio::test]
+async fn test_other_owners_vault_is_rejected() {
+    let owner = Keypair::new();
+    let attacker = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut program_test = program_test(&owner.pubkey(), 1_000_000_000);
     program_test.add_account(
-        vault.pubkey(),
-        solana_sdk::account::Account {
-            lamports: 100000,
+        attacker.pubkey(),
+        Account {
+            lamports: OWNER_LAMPORTS,
             data: vec![],
-            owner: solana_sdk::system_program::ID,
+            owner: system_program::ID,
             executable: false,
             rent_epoch: 0,
         },
     );
-    
-    let (mut banks_client, payer, recent_blockhash) = program_test.start().await;
-    
-    // Test withdraw instruction
-    let withdraw_amount: u64 = 50000;
-    let mut instruction_data = vec![1]; // Discriminator for withdraw
-    instruction_data.extend_from_slice(&withdraw_amount.to_le_bytes());
-    
-    let instruction = solana_sdk::instruction::Instruction {
-        program_id: program_id,
-        accounts: vec![
-            solana_sdk::instruction::AccountMeta::new(owner.pubkey(), true),
-            solana_sdk::instruction::AccountMeta::new(vault.pubkey(), false),
-            solana_sdk::instruction::AccountMeta::new_readonly(solana_sdk::system_program::ID, false),
-        ],
-        data: instruction_data,
-    };
-    
-    let transaction = Transaction::new_signed_with_payer(
-        &[instruction],
-        Some(&payer.pubkey()),
-        &[&payer, &owner],
-        recent_blockhash,
-    );
-    
-    // Send transaction and check result
-    let result = banks_client.process_transaction(transaction).await;
-    println!("Withdraw transaction result: {:?}", result);
-    assert!(result.is_ok());
+    let mut context = program_test.start_with_context().await;
+
+    let instruction = vault_instruction(&attacker.pubkey(), &vault, true, instruction_data(WITHDRAW, None));
+    let result = process(&mut context, instruction, &[&attacker]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidAccountData);
+    assert_eq!(lamports(&mut context, &vault).await, 1_000_000_000);
+}
+
+#[tokio::test]
+async fn test_missing_signer_is_rejected() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 1_000_000_000)
+        .start_with_context()
+        .await;
+
+    // owner 没有签名
+    let instruction = vault_instruction(&owner.pubkey(), &vault, false, instruction_data(WITHDRAW, None));
+    let result = process(&mut context, instruction, &[]).await;
+
+    assert_instruction_error(result, InstructionError::MissingRequiredSignature);
+    assert_eq!(lamports(&mut context, &vault).await, 1_000_000_000);
+}
+
+#[tokio::test]
+async fn test_empty_data_is_rejected() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 0).start_with_context().await;
+
+    // 缺少 discriminator
+    let instruction = vault_instruction(&owner.pubkey(), &vault, true, vec![]);
+    let result = process(&mut context, instruction, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidInstructionData);
+}
+
+#[tokio::test]
+async fn test_short_amount_is_rejected() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 1_000_000_000)
+        .start_with_context()
+        .await;
+
+    // 金额只有 4 字节，不能被当成 "提取全部"
+    let mut data = vec![WITHDRAW];
+    data.extend_from_slice(&1_000u32.to_le_bytes());
+    let instruction = vault_instruction(&owner.pubkey(), &vault, true, data);
+    let result = process(&mut context, instruction, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidInstructionData);
+    assert_eq!(lamports(&mut context, &vault).await, 1_000_000_000);
+}
+
+#[tokio::test]
+async fn test_unknown_discriminator_is_rejected() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&owner.pubkey(), 0).start_with_context().await;
+
+    let instruction = vault_instruction(&owner.pubkey(), &vault, true, instruction_data(7, Some(1)));
+    let result = process(&mut context, instruction, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidInstructionData);
 }
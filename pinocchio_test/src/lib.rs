@@ -2,10 +2,11 @@
 
 use core::convert::TryFrom;
 use pinocchio::{
-    cpi::invoke,
-    instruction::{InstructionAccount, InstructionView},
+    cpi::{Seed, Signer},
+    sysvars::{rent::Rent, Sysvar},
     AccountView, Address, entrypoint, error::ProgramError, nostd_panic_handler, ProgramResult,
 };
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
 
 entrypoint!(process_instruction);
 nostd_panic_handler!();
@@ -41,10 +42,140 @@ fn process_instruction(
             let withdraw = Withdraw::try_from(accounts)?;
             withdraw.process(program_id)
         },
+        Some((discriminator, _)) if *discriminator == Initialize::DISCRIMINATOR => {
+            let initialize = Initialize::try_from(accounts)?;
+            initialize.process(program_id)
+        },
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
 
+/// vault 的 PDA 种子前缀，完整种子为 [b"vault", owner]
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// 验证 vault 是 owner 对应的 PDA，返回 bump
+fn check_vault_address(
+    vault: &AccountView,
+    owner: &AccountView,
+    program_id: &Address,
+) -> Result<u8, ProgramError> {
+    let (vault_address, bump) =
+        Address::find_program_address(&[VAULT_SEED, owner.address().as_ref()], program_id);
+
+    if vault_address.ne(vault.address()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    Ok(bump)
+}
+
+#[derive(Debug)]
+pub struct Initialize {
+    pub accounts: InitializeAccounts,
+}
+
+impl Initialize {
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub fn process(self, program_id: &Address) -> ProgramResult {
+        let vault = self.accounts.vault;
+        let owner = self.accounts.owner;
+
+        // 验证 owner 是签名者
+        if !owner.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let bump = [check_vault_address(&vault, &owner, program_id)?];
+        let seeds = [
+            Seed::from(VAULT_SEED),
+            Seed::from(owner.address().as_ref()),
+            Seed::from(bump.as_ref()),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        let rent_exemption = Rent::get()?.minimum_balance(0);
+        let lamports = vault.lamports();
+        if lamports == 0 {
+            // 由 owner 支付租金，创建归本程序所有的 vault
+            return CreateAccount {
+                from: &owner,
+                to: &vault,
+                lamports: rent_exemption,
+                space: 0,
+                owner: program_id,
+            }
+            .invoke_signed(&signers);
+        }
+
+        // vault 只能创建一次。但任何人都可以提前往 PDA 转入 lamports，此时 CreateAccount 会失败，
+        // 所以只要它仍归 System Program 所有，就补足租金后 Allocate + Assign
+        if !vault.owned_by(&SYSTEM_PROGRAM_ID) {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if rent_exemption > lamports {
+            Transfer {
+                from: &owner,
+                to: &vault,
+                lamports: rent_exemption - lamports,
+            }
+            .invoke()?;
+        }
+        Allocate {
+            account: &vault,
+            space: 0,
+        }
+        .invoke_signed(&signers)?;
+        Assign {
+            account: &vault,
+            owner: program_id,
+        }
+        .invoke_signed(&signers)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct InitializeAccounts {
+    pub vault: AccountView,
+    pub owner: AccountView,
+    pub system_program: AccountView,
+}
+
+impl TryFrom<&[AccountView]> for Initialize {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &[AccountView]) -> Result<Self, Self::Error> {
+        let accounts = InitializeAccounts::try_from(accounts)?;
+        Ok(Initialize { accounts })
+    }
+}
+
+impl TryFrom<&[AccountView]> for InitializeAccounts {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &[AccountView]) -> Result<Self, Self::Error> {
+        if accounts.len() < 3 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        let vault = &accounts[0];
+        let owner = &accounts[1];
+        let system_program = &accounts[2];
+
+        if system_program.address() != &SYSTEM_PROGRAM_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(InitializeAccounts {
+            vault: vault.clone(),
+            owner: owner.clone(),
+            system_program: system_program.clone(),
+        })
+    }
+}
+
 #[derive(Debug)]
 pub struct Deposit {
     pub lamports: u64,
@@ -70,8 +201,20 @@ impl Deposit {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        // 这里我们只验证操作，实际的转账应该由用户通过 System Program 完成
-        // 但为了简化测试，我们假设转账已经完成
+        // 验证 vault 属于这个 owner
+        check_vault_address(&vault, &owner, program_id)?;
+
+        if !vault.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 通过 CPI 调用 System Program，把 lamports 从 owner 转入 vault
+        Transfer {
+            from: &owner,
+            to: &vault,
+            lamports: self.lamports,
+        }
+        .invoke()?;
 
         Ok(())
     }
@@ -81,6 +224,7 @@ impl Deposit {
 pub struct DepositAccounts {
     pub vault: AccountView,
     pub owner: AccountView,
+    pub system_program: AccountView,
 }
 
 impl TryFrom<(&[u8], &[AccountView])> for Deposit {
@@ -94,6 +238,10 @@ impl TryFrom<(&[u8], &[AccountView])> for Deposit {
             return Err(ProgramError::InvalidInstructionData);
         };
 
+        if lamports == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
         Ok(Deposit {
             lamports,
             accounts,
@@ -105,16 +253,22 @@ impl TryFrom<&[AccountView]> for DepositAccounts {
     type Error = ProgramError;
 
     fn try_from(accounts: &[AccountView]) -> Result<Self, Self::Error> {
-        if accounts.len() < 2 {
+        if accounts.len() < 3 {
             return Err(ProgramError::NotEnoughAccountKeys);
         }
 
         let vault = &accounts[0];
         let owner = &accounts[1];
+        let system_program = &accounts[2];
+
+        if system_program.address() != &SYSTEM_PROGRAM_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
 
         Ok(DepositAccounts {
             vault: vault.clone(),
             owner: owner.clone(),
+            system_program: system_program.clone(),
         })
     }
 }
@@ -143,28 +297,29 @@ impl Withdraw {
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        // 计算可以提取的金额（保留 rent exempt 余额）
-        let rent_exempt = 1_000_000_000;
-        let lamports = vault.lamports() - rent_exempt;
-
-        // 由于 vault 账户由我们的程序拥有，我们可以直接修改它的余额
-        // 我们可以将 vault 账户的余额减少，将 owner 账户的余额增加
-        // 注意：这只在我们的程序拥有 vault 账户时才有效
-        // 由于 owner 账户由 System Program 拥有，我们不能直接修改它的余额
-        // 所以，我们需要使用 CPI 调用 System Program 的 transfer 指令
-        // 但是，由于这是一个测试程序，我们可以简化这个过程
-        // 我们只需要验证取款操作的逻辑是否正确
-        // 所以，我们只需要确保 vault 账户是可变的
-        
-        // 确保 vault 账户是可变的
-        let new_vault_balance = vault.lamports() - lamports;
-        vault.set_lamports(new_vault_balance);
-        
-        // 注意：在实际应用中，我们需要使用 CPI 调用 System Program 的 transfer 指令
-        // 来将这些 lamports 从 vault 账户转移到 owner 账户
-        // 但是，由于 System Program 的 transfer 指令要求发送方是签名者
-        // 这需要更复杂的逻辑，涉及到 invoke_signed
-        
+        // 验证 vault 属于这个 owner，其他签名者不能提取
+        check_vault_address(&vault, &owner, program_id)?;
+
+        if !vault.is_writable() || !owner.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 保留 vault 的 rent exempt 余额，其余全部提取
+        let rent_floor = Rent::get()?.minimum_balance(vault.data_len());
+        let lamports = vault
+            .lamports()
+            .checked_sub(rent_floor)
+            .ok_or(ProgramError::InsufficientFunds)?;
+
+        // vault 由我们的程序拥有，可以直接扣减它的余额；
+        // 给 owner 增加余额不需要拥有 owner 账户
+        let new_owner_balance = owner
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        vault.set_lamports(rent_floor);
+        owner.set_lamports(new_owner_balance);
+
         Ok(())
     }
 }
@@ -213,3 +368,9 @@ impl Withdraw {
         &self.accounts
     }
 }
+
+impl Initialize {
+    pub fn accounts(&self) -> &InitializeAccounts {
+        &self.accounts
+    }
+}
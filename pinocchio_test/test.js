@@ -13,9 +13,11 @@ const payer = web3.Keypair.generate();
 const owner = web3.Keypair.generate();
 
 async function testProgram() {
-  // 使用 createAccountWithSeed 派生 vault 地址
-  const seed = 'vault';
-  const vault = await web3.PublicKey.createWithSeed(owner.publicKey, seed, programId);
+  // vault 是 [b"vault", owner] PDA
+  const [vault] = web3.PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), owner.publicKey.toBuffer()],
+    programId
+  );
   
   console.log('Testing Pinocchio Test Program...');
   console.log(`Program ID: ${programId.toBase58()}`);
@@ -46,25 +48,35 @@ async function testProgram() {
   console.log(`Payer: ${payerBalance / web3.LAMPORTS_PER_SOL} SOL`);
   console.log(`Owner: ${ownerBalance / web3.LAMPORTS_PER_SOL} SOL`);
   
-  // 创建 vault 账户 - 使用 createAccountWithSeed
+  // 创建 vault 账户 - 使用 Initialize 指令，由 owner 支付租金
   console.log('\nCreating vault account...');
-  const rentExemptAmount = await connection.getMinimumBalanceForRentExemption(0);
-  
-  const createVaultInstruction = web3.SystemProgram.createAccountWithSeed({
-    fromPubkey: payer.publicKey,
-    newAccountPubkey: vault,
-    basePubkey: owner.publicKey,
-    seed: seed,
-    lamports: rentExemptAmount,
-    space: 0,
-    programId: programId
+  const initializeInstruction = new web3.TransactionInstruction({
+    keys: [
+      {
+        pubkey: vault,
+        isSigner: false,
+        isWritable: true
+      },
+      {
+        pubkey: owner.publicKey,
+        isSigner: true,
+        isWritable: true
+      },
+      {
+        pubkey: web3.SystemProgram.programId,
+        isSigner: false,
+        isWritable: false
+      }
+    ],
+    programId,
+    data: Buffer.from([2]) // Initialize discriminator
   });
   
-  const createVaultTransaction = new web3.Transaction().add(createVaultInstruction);
+  const createVaultTransaction = new web3.Transaction().add(initializeInstruction);
   const createVaultSignature = await web3.sendAndConfirmTransaction(
     connection,
     createVaultTransaction,
-    [payer, owner]
+    [owner]
   );
   console.log(`Vault account created: ${createVaultSignature}`);
   
@@ -73,15 +85,7 @@ async function testProgram() {
   const depositAmount = Math.floor(web3.LAMPORTS_PER_SOL * 0.5);
   console.log(`Deposit amount: ${depositAmount} lamports (${depositAmount / web3.LAMPORTS_PER_SOL} SOL)`);
   
-  // 1. 首先使用 System Program 向 vault 账户转账
-  console.log('Transferring SOL to vault...');
-  const transferInstruction = web3.SystemProgram.transfer({
-    fromPubkey: owner.publicKey,
-    toPubkey: vault,
-    lamports: depositAmount
-  });
-  
-  // 2. 然后调用我们的程序进行验证
+  // 程序内部通过 CPI 调用 System Program 完成转账
   // 将金额转换为8字节的字节数组（小端序）
   const amountBuffer = Buffer.alloc(8);
   amountBuffer.writeBigUInt64LE(BigInt(depositAmount));
@@ -96,6 +100,11 @@ async function testProgram() {
       {
         pubkey: owner.publicKey,
         isSigner: true,
+        isWritable: true
+      },
+      {
+        pubkey: web3.SystemProgram.programId,
+        isSigner: false,
         isWritable: false
       }
     ],
@@ -106,10 +115,7 @@ async function testProgram() {
     ])
   });
   
-  // 组合两个指令到一个交易中
-  const depositTransaction = new web3.Transaction()
-    .add(transferInstruction)
-    .add(depositInstruction);
+  const depositTransaction = new web3.Transaction().add(depositInstruction);
   
   try {
     const depositSignature = await web3.sendAndConfirmTransaction(
@@ -0,0 +1,186 @@
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+// 程序通过 target/deploy/blueshift_vault.so 加载（本 crate 的包名是 blueshift_vault）。
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use std::str::FromStr;
+
+const DEPOSIT: u8 = 0;
+const WITHDRAW: u8 = 1;
+const INITIALIZE: u8 = 2;
+const OWNER_LAMPORTS: u64 = 10_000_000_000;
+
+fn program_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn vault_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"vault", owner.as_ref()], &program_id()).0
+}
+
+fn account(lamports: u64, owner: Pubkey) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn program_test(owners: &[&Pubkey]) -> ProgramTest {
+    // processor 传 None：加载 target/deploy 下的 SBF 程序
+    let mut program_test = ProgramTest::new("blueshift_vault", program_id(), None);
+    program_test.prefer_bpf(true);
+    for owner in owners {
+        program_test.add_account(**owner, account(OWNER_LAMPORTS, system_program::ID));
+    }
+    program_test
+}
+
+// 三个指令的账户顺序都是 [vault, owner, system_program]
+fn vault_instruction(vault: &Pubkey, owner: &Pubkey, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*vault, false),
+            AccountMeta::new(*owner, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    }
+}
+
+fn deposit_data(amount: u64) -> Vec<u8> {
+    let mut data = vec![DEPOSIT];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+async fn process(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), BanksClientError> {
+    let mut all_signers = vec![&context.payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &all_signers,
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+async fn get_account(context: &mut ProgramTestContext, address: &Pubkey) -> Account {
+    context.banks_client.get_account(*address).await.unwrap().unwrap()
+}
+
+fn assert_instruction_error(result: Result<(), BanksClientError>, expected: InstructionError) {
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, expected)
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_deposit_withdraw() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut context = program_test(&[&owner.pubkey()]).start_with_context().await;
+
+    let initialize = vault_instruction(&vault, &owner.pubkey(), vec![INITIALIZE]);
+    process(&mut context, initialize, &[&owner]).await.unwrap();
+    let rent_floor = Rent::default().minimum_balance(0);
+    let vault_account = get_account(&mut context, &vault).await;
+    assert_eq!(vault_account.owner, program_id());
+    assert_eq!(vault_account.lamports, rent_floor);
+
+    let amount = 1_000_000_000;
+    let deposit = vault_instruction(&vault, &owner.pubkey(), deposit_data(amount));
+    process(&mut context, deposit, &[&owner]).await.unwrap();
+    assert_eq!(get_account(&mut context, &vault).await.lamports, rent_floor + amount);
+
+    let withdraw = vault_instruction(&vault, &owner.pubkey(), vec![WITHDRAW]);
+    process(&mut context, withdraw, &[&owner]).await.unwrap();
+    assert_eq!(get_account(&mut context, &vault).await.lamports, rent_floor);
+    assert_eq!(
+        get_account(&mut context, &owner.pubkey()).await.lamports,
+        OWNER_LAMPORTS - rent_floor
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_prefunded_vault() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut program_test = program_test(&[&owner.pubkey()]);
+    // 任何人都可以提前往 PDA 转账，这不能让 initialize 永远失败
+    program_test.add_account(vault, account(1, system_program::ID));
+    let mut context = program_test.start_with_context().await;
+
+    let initialize = vault_instruction(&vault, &owner.pubkey(), vec![INITIALIZE]);
+    process(&mut context, initialize, &[&owner]).await.unwrap();
+
+    let vault_account = get_account(&mut context, &vault).await;
+    assert_eq!(vault_account.owner, program_id());
+    assert_eq!(vault_account.lamports, Rent::default().minimum_balance(0));
+}
+
+#[tokio::test]
+async fn test_initialize_twice_is_rejected() {
+    let owner = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut program_test = program_test(&[&owner.pubkey()]);
+    program_test.add_account(vault, account(Rent::default().minimum_balance(0), program_id()));
+    let mut context = program_test.start_with_context().await;
+
+    let initialize = vault_instruction(&vault, &owner.pubkey(), vec![INITIALIZE]);
+    let result = process(&mut context, initialize, &[&owner]).await;
+
+    assert_instruction_error(result, InstructionError::AccountAlreadyInitialized);
+}
+
+#[tokio::test]
+async fn test_wrong_pda_is_rejected() {
+    let owner = Keypair::new();
+    // 一个归本程序所有的普通地址，而不是 [b"vault", owner] PDA
+    let not_vault = Keypair::new().pubkey();
+    let mut program_test = program_test(&[&owner.pubkey()]);
+    program_test.add_account(not_vault, account(1_000_000_000, program_id()));
+    let mut context = program_test.start_with_context().await;
+
+    let initialize = vault_instruction(&Keypair::new().pubkey(), &owner.pubkey(), vec![INITIALIZE]);
+    let result = process(&mut context, initialize, &[&owner]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+
+    let withdraw = vault_instruction(&not_vault, &owner.pubkey(), vec![WITHDRAW]);
+    let result = process(&mut context, withdraw, &[&owner]).await;
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    assert_eq!(get_account(&mut context, &not_vault).await.lamports, 1_000_000_000);
+}
+
+#[tokio::test]
+async fn test_other_owners_vault_is_rejected() {
+    let owner = Keypair::new();
+    let attacker = Keypair::new();
+    let vault = vault_pda(&owner.pubkey());
+    let mut program_test = program_test(&[&owner.pubkey(), &attacker.pubkey()]);
+    program_test.add_account(vault, account(1_000_000_000, program_id()));
+    let mut context = program_test.start_with_context().await;
+
+    let withdraw = vault_instruction(&vault, &attacker.pubkey(), vec![WITHDRAW]);
+    let result = process(&mut context, withdraw, &[&attacker]).await;
+
+    assert_instruction_error(result, InstructionError::InvalidSeeds);
+    assert_eq!(get_account(&mut context, &vault).await.lamports, 1_000_000_000);
+}
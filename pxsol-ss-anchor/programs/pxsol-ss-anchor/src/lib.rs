@@ -13,6 +13,7 @@ pub mod pxsol_ss_anchor {
         account_user_pda.auth = account_user.key();
         account_user_pda.bump = ctx.bumps.user_pda;
         account_user_pda.data = Vec::new();
+        account_user_pda.touch()?;
         Ok(())
     }
     
@@ -31,46 +32,200 @@ pub mod pxsol_ss_anchor {
         pub system_program: Program<'info, System>,
 
     }
+
+    // Create a named blob owned by the user, so that one user can keep several data accounts.
+    pub fn init_named(ctx: Context<InitNamed>, name: String) -> Result<()> {
+        // An empty name would derive the same address as `init`, and seeds are limited to 32 bytes.
+        require!(!name.is_empty() && name.len() <= 32, PxsolError::InvalidData);
+        let account_user = &ctx.accounts.user;
+        let account_user_pda = &mut ctx.accounts.user_pda;
+        account_user_pda.auth = account_user.key();
+        account_user_pda.bump = ctx.bumps.user_pda;
+        account_user_pda.data = Vec::new();
+        account_user_pda.touch()?;
+        msg!("Init named blob: {}", name);
+        Ok(())
+    }
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct InitNamed<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(
+        init,
+        payer = user,
+        seeds = [b"seeds", user.key().as_ref(), name.as_bytes()],
+        bump,
+        space = Data::space_for(0)
+    )]
+    pub user_pda: Account<'info, Data>,
+    pub system_program: Program<'info, System>,
+}
+
+    // Hand the write rights of a data account to another key, e.g. a hot key or a program PDA.
+    // Only the auth bytes are rewritten, so the cost does not depend on the blob size.
+    pub fn set_auth(ctx: Context<SetAuth>, new_auth: Pubkey) -> Result<()> {
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        Data::check_raw(&account_user_pda, ctx.accounts.user.key)?;
+        account_user_pda.try_borrow_mut_data()?[Data::AUTH_OFFSET..Data::AUTH_OFFSET + 32]
+            .copy_from_slice(new_auth.as_ref());
+        Ok(())
+    }
+
+#[derive(Accounts)]
+pub struct SetAuth<'info> {
+    pub user: Signer<'info>,
+    /// CHECK: Owner is checked here, the discriminator and auth by `Data::check_raw`.
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub user_pda: UncheckedAccount<'info>,
+}
     #[account]
 pub struct Data {
-    pub auth: Pubkey, // The owner of this pda account
-    pub bump: u8,     // The bump to generate the PDA
-    pub data: Vec<u8> // The content, arbitrary bytes
+    pub auth: Pubkey,    // The key allowed to write this pda account
+    pub bump: u8,        // The bump to generate the PDA
+    pub hash: [u8; 32],  // The sha256 of the content, zero if the content was changed in chunks and not sealed yet
+    pub len: u32,        // The content length covered by hash
+    pub slot: u64,       // The slot of the last modification
+    pub version: u64,    // Increased by one on every modification
+    pub data: Vec<u8>    // The content, arbitrary bytes
 }
 
 impl Data {
+    // Byte offsets of the serialized fields, used by the raw (chunked) instructions.
+    pub const AUTH_OFFSET: usize = 8;
+    pub const META_OFFSET: usize = 8 + 32 + 1;
+    pub const LEN_OFFSET: usize = 8 + 32 + 1 + 32 + 4 + 8 + 8;
+    pub const DATA_OFFSET: usize = 8 + 32 + 1 + 32 + 4 + 8 + 8 + 4;
+    // Accounts created before the integrity metadata existed: discriminator | auth | bump | vec len | content.
+    pub const LEGACY_DATA_OFFSET: usize = 8 + 32 + 1 + 4;
+
     pub fn space_for(data_len: usize) -> usize {
-        // 8 (discriminator) + 32 (auth) + 1 (bump) + 4 (vec len) + data_len
-        8 + 32 + 1 + 4 + data_len
+        // 8 (discriminator) + 32 (auth) + 1 (bump) + 32 (hash) + 4 (len) + 8 (slot) + 8 (version) + 4 (vec len) +
+        // data_len
+        8 + 32 + 1 + 32 + 4 + 8 + 8 + 4 + data_len
+    }
+
+    // Refresh the integrity metadata after the content has changed.
+    pub fn touch(&mut self) -> Result<()> {
+        self.hash = solana_sha256_hasher::hash(&self.data).to_bytes();
+        self.len = self.data.len() as u32;
+        self.slot = Clock::get()?.slot;
+        self.version = self.version.checked_add(1).ok_or(PxsolError::InvalidData)?;
+        Ok(())
+    }
+
+    // Same as `touch`, for an account accessed through its raw bytes. Hashing the whole blob after every chunk would
+    // make an upload quadratic, so the hash is cleared here and computed once by `seal`.
+    pub fn touch_raw(info: &AccountInfo) -> Result<()> {
+        let mut raw = info.try_borrow_mut_data()?;
+        let slot = Clock::get()?.slot;
+        let version_offset = Data::META_OFFSET + 32 + 4 + 8;
+        let version = u64::from_le_bytes(raw[version_offset..version_offset + 8].try_into().unwrap())
+            .checked_add(1)
+            .ok_or(PxsolError::InvalidData)?;
+        let meta = &mut raw[Data::META_OFFSET..Data::LEN_OFFSET];
+        meta[..36].fill(0);
+        meta[36..44].copy_from_slice(&slot.to_le_bytes());
+        meta[44..52].copy_from_slice(&version.to_le_bytes());
+        Ok(())
+    }
+
+    // Check the discriminator and the stored auth without deserializing the data vec, and return the stored length.
+    pub fn check_raw(info: &AccountInfo, auth: &Pubkey) -> Result<usize> {
+        let raw = info.try_borrow_data()?;
+        require!(raw.len() >= Data::DATA_OFFSET, PxsolError::InvalidData);
+        require!(raw[..8] == *Data::DISCRIMINATOR, PxsolError::InvalidData);
+        require!(raw[Data::AUTH_OFFSET..Data::AUTH_OFFSET + 32] == auth.to_bytes(), PxsolError::Unauthorized);
+        let len = u32::from_le_bytes(raw[Data::LEN_OFFSET..Data::DATA_OFFSET].try_into().unwrap()) as usize;
+        require!(Data::space_for(len) == raw.len(), PxsolError::InvalidData);
+        Ok(len)
+    }
+
+    // Same as `check_raw` for an account in the legacy layout, see `LEGACY_DATA_OFFSET`.
+    pub fn check_legacy_raw(info: &AccountInfo, auth: &Pubkey) -> Result<usize> {
+        let raw = info.try_borrow_data()?;
+        require!(raw.len() >= Data::LEGACY_DATA_OFFSET, PxsolError::InvalidData);
+        require!(raw[..8] == *Data::DISCRIMINATOR, PxsolError::InvalidData);
+        require!(raw[Data::AUTH_OFFSET..Data::AUTH_OFFSET + 32] == auth.to_bytes(), PxsolError::Unauthorized);
+        if raw.len() >= Data::DATA_OFFSET {
+            let len = u32::from_le_bytes(raw[Data::LEN_OFFSET..Data::DATA_OFFSET].try_into().unwrap()) as usize;
+            require!(Data::space_for(len) != raw.len(), PxsolError::InvalidData);
+        }
+        let len = u32::from_le_bytes(raw[Data::META_OFFSET..Data::LEGACY_DATA_OFFSET].try_into().unwrap()) as usize;
+        require!(Data::LEGACY_DATA_OFFSET + len == raw.len(), PxsolError::InvalidData);
+        Ok(len)
+    }
+
+    // Resize the account to hold data_len bytes and update the vec length prefix.
+    pub fn resize<'info>(
+        info: &AccountInfo<'info>,
+        user: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        data_len: usize,
+    ) -> Result<()> {
+        Data::reserve(info, user, system_program, Data::space_for(data_len))?;
+        info.try_borrow_mut_data()?[Data::LEN_OFFSET..Data::DATA_OFFSET]
+            .copy_from_slice(&(data_len as u32).to_le_bytes());
+        Ok(())
+    }
+
+    // Resize the account to space bytes. Extra rent is paid by the user; surplus rent is moved back to the user
+    // directly, since the account is owned by this program.
+    pub fn reserve<'info>(
+        info: &AccountInfo<'info>,
+        user: &AccountInfo<'info>,
+        system_program: &AccountInfo<'info>,
+        space: usize,
+    ) -> Result<()> {
+        let rent_exemption = Rent::get()?.minimum_balance(space);
+        let hold = info.lamports();
+        if rent_exemption > hold {
+            let cpictx = CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer { from: user.clone(), to: info.clone() },
+            );
+            system_program::transfer(cpictx, rent_exemption - hold)?;
+        }
+        if hold > rent_exemption {
+            let refund = hold - rent_exemption;
+            **info.try_borrow_mut_lamports()? -= refund;
+            **user.try_borrow_mut_lamports()? += refund;
+        }
+        info.resize(space)?;
+        Ok(())
     }
 }
 
-    pub fn update(ctx: Context<Update>, data: Vec<u8>) -> Result<()> {
+    // Replace the content. With expected_version set, the update fails if someone else has modified the account
+    // since the caller read it.
+    pub fn update(ctx: Context<Update>, data: Vec<u8>, expected_version: Option<u64>) -> Result<()> {
         let account_user = &ctx.accounts.user;
         let account_user_pda = &mut ctx.accounts.user_pda;
         // Authorization: only the stored authority can update.
         require_keys_eq!(account_user_pda.auth, account_user.key(), PxsolError::Unauthorized);
+        if let Some(expected_version) = expected_version {
+            require_eq!(account_user_pda.version, expected_version, PxsolError::VersionMismatch);
+        }
         // At this point, Anchor has already reallocated the account according to the `realloc = ...` constraint
         // (using `new_data.len()`), pulling extra lamports from auth if needed to maintain rent-exemption.
         account_user_pda.data = data;
+        account_user_pda.touch()?;
         // If the account was shrunk, Anchor won't automatically refund excess lamports. Refund any surplus (over the
         // new rent-exempt minimum) back to the user.
         let account_user_pda_info = account_user_pda.to_account_info();
         let rent = Rent::get()?;
         let rent_exemption = rent.minimum_balance(account_user_pda_info.data_len());
-        let hold = **account_user_pda_info.lamports.borrow();
+        let hold = account_user_pda_info.lamports();
         if hold > rent_exemption {
-            let refund = hold.saturating_sub(rent_exemption);
-            // Transfer lamports from PDA to user using the PDA as signer.
-            let signer_seeds: &[&[u8]] = &[b"seeds", account_user.key.as_ref(), &[account_user_pda.bump]];
-            let signer = &[signer_seeds];
-            let cpictx = CpiContext::new_with_signer(
-                ctx.accounts.system_program.to_account_info(),
-                system_program::Transfer { from: account_user_pda_info.clone(), to: account_user.to_account_info() },
-                signer,
-            );
-            // It's okay if refund equals current - min_rent; system program enforces balances.
-            system_program::transfer(cpictx, refund)?;
+            // The PDA is owned by this program, not by the System Program, so a system transfer out of it would fail.
+            // Debit its lamports directly instead.
+            let refund = hold - rent_exemption;
+            **account_user_pda_info.try_borrow_mut_lamports()? -= refund;
+            **account_user.to_account_info().try_borrow_mut_lamports()? += refund;
         }
         Ok(())
     }
@@ -81,10 +236,9 @@ impl Data {
 pub struct Update<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
+    // The account is not re-derived from the signer: it may be a named blob, or its auth may have been handed over.
     #[account(
         mut,
-        seeds = [b"seeds", user.key().as_ref()],
-        bump = user_pda.bump,
         realloc = Data::space_for(new_data.len()),
         realloc::payer = user,
         realloc::zero = false,
@@ -93,6 +247,127 @@ pub struct Update<'info> {
     pub user_pda: Account<'info, Data>,
     pub system_program: Program<'info, System>,
 }
+    // Write bytes at offset, growing the stored data if the write goes past its end. Writes may not leave a gap, so
+    // a large blob is uploaded as consecutive chunks over several transactions.
+    pub fn write_at(ctx: Context<Chunk>, offset: u32, bytes: Vec<u8>) -> Result<()> {
+        let account_user = ctx.accounts.user.to_account_info();
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        let len = Data::check_raw(&account_user_pda, account_user.key)?;
+        let offset = offset as usize;
+        require!(offset <= len, PxsolError::InvalidOffset);
+        let end = offset.checked_add(bytes.len()).ok_or(PxsolError::InvalidOffset)?;
+        require!(end <= u32::MAX as usize, PxsolError::InvalidOffset);
+        if end > len {
+            Data::resize(&account_user_pda, &account_user, &ctx.accounts.system_program.to_account_info(), end)?;
+        }
+        account_user_pda.try_borrow_mut_data()?[Data::DATA_OFFSET + offset..Data::DATA_OFFSET + end]
+            .copy_from_slice(&bytes);
+        Data::touch_raw(&account_user_pda)?;
+        Ok(())
+    }
+
+    // Shrink the stored data to len bytes and refund the surplus rent to the user.
+    pub fn truncate(ctx: Context<Chunk>, len: u32) -> Result<()> {
+        let account_user = ctx.accounts.user.to_account_info();
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        let old_len = Data::check_raw(&account_user_pda, account_user.key)?;
+        let len = len as usize;
+        require!(len <= old_len, PxsolError::InvalidOffset);
+        Data::resize(&account_user_pda, &account_user, &ctx.accounts.system_program.to_account_info(), len)?;
+        Data::touch_raw(&account_user_pda)?;
+        Ok(())
+    }
+
+    // Convert an account created before the integrity metadata existed to the current layout. The content is kept, the
+    // hash stays empty until `seal`. Every other instruction rejects legacy accounts.
+    pub fn migrate(ctx: Context<Chunk>) -> Result<()> {
+        let account_user = ctx.accounts.user.to_account_info();
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        let len = Data::check_legacy_raw(&account_user_pda, account_user.key)?;
+        Data::reserve(
+            &account_user_pda,
+            &account_user,
+            &ctx.accounts.system_program.to_account_info(),
+            Data::space_for(len),
+        )?;
+        {
+            let mut raw = account_user_pda.try_borrow_mut_data()?;
+            raw.copy_within(Data::LEGACY_DATA_OFFSET..Data::LEGACY_DATA_OFFSET + len, Data::DATA_OFFSET);
+            raw[Data::META_OFFSET..Data::LEN_OFFSET].fill(0);
+            raw[Data::LEN_OFFSET..Data::DATA_OFFSET].copy_from_slice(&(len as u32).to_le_bytes());
+        }
+        Data::touch_raw(&account_user_pda)?;
+        Ok(())
+    }
+
+    // Hash the content after it was uploaded with `write_at` and `truncate`. The content, slot and version are left
+    // unchanged. The sha256 syscall costs about one CU per two bytes, so blobs above roughly 2.5 MB cannot be sealed in
+    // a single transaction.
+    pub fn seal(ctx: Context<Seal>) -> Result<()> {
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        let len = Data::check_raw(&account_user_pda, ctx.accounts.user.key)?;
+        let mut raw = account_user_pda.try_borrow_mut_data()?;
+        let hash = solana_sha256_hasher::hash(&raw[Data::DATA_OFFSET..]).to_bytes();
+        let meta = &mut raw[Data::META_OFFSET..Data::LEN_OFFSET];
+        meta[..32].copy_from_slice(&hash);
+        meta[32..36].copy_from_slice(&(len as u32).to_le_bytes());
+        Ok(())
+    }
+
+#[derive(Accounts)]
+pub struct Seal<'info> {
+    pub user: Signer<'info>,
+    /// CHECK: Owner is checked here, the discriminator and auth by `Data::check_raw`.
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub user_pda: UncheckedAccount<'info>,
+}
+
+    // Delete the data account and return all of its rent to the user. The account can be created again with `init`.
+    // The account is closed by hand instead of with `close = user`, which would deserialize the whole blob first.
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        let account_user = ctx.accounts.user.to_account_info();
+        let account_user_pda = ctx.accounts.user_pda.to_account_info();
+        Data::check_raw(&account_user_pda, account_user.key)?;
+        let lamports = account_user_pda.lamports();
+        **account_user_pda.try_borrow_mut_lamports()? = 0;
+        **account_user.try_borrow_mut_lamports()? =
+            account_user.lamports().checked_add(lamports).ok_or(PxsolError::InvalidData)?;
+        account_user_pda.try_borrow_mut_data()?.fill(0);
+        account_user_pda.assign(&system_program::ID);
+        account_user_pda.resize(0)?;
+        Ok(())
+    }
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Owner is checked here, the discriminator and auth by `Data::check_raw`.
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub user_pda: UncheckedAccount<'info>,
+}
+
+// Accounts for the raw chunk instructions. The data account is not deserialized, so the cost does not depend on the
+// blob size and blobs larger than the heap can be stored.
+#[derive(Accounts)]
+pub struct Chunk<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// CHECK: Owner is checked here, the discriminator and auth by `Data::check_raw`.
+    #[account(
+        mut,
+        owner = crate::ID,
+    )]
+    pub user_pda: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
 #[error_code]
 pub enum PxsolError {
     #[msg("You are not authorized to perform this action")]
@@ -101,6 +376,12 @@ pub enum PxsolError {
     // 可以添加更多错误
     #[msg("Invalid data provided")]
     InvalidData,
+
+    #[msg("Offset or length is outside the stored data")]
+    InvalidOffset,
+
+    #[msg("The account was modified since the expected version")]
+    VersionMismatch,
 }
 }
 
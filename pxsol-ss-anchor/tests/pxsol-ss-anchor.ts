@@ -40,7 +40,7 @@ describe("pxsol-ss-anchor", () => {
     if (!(await walletPdaData()).equals(poemInitial)) throw new Error("mismatch");
 
     await program.methods
-      .update(poemEnglish)
+      .update(poemEnglish, null)
       .accounts({
         user: wallet.publicKey,
         userPda: walletPda,
@@ -51,7 +51,7 @@ describe("pxsol-ss-anchor", () => {
     if (!(await walletPdaData()).equals(poemEnglish)) throw new Error("mismatch");
 
     await program.methods
-      .update(poemChinese)
+      .update(poemChinese, null)
       .accounts({
         user: wallet.publicKey,
         userPda: walletPda,
@@ -61,4 +61,271 @@ describe("pxsol-ss-anchor", () => {
       .rpc();
     if (!(await walletPdaData()).equals(poemChinese)) throw new Error("mismatch");
   });
+
+  it("Write in chunks and truncate", async () => {
+    const accounts = {
+      user: wallet.publicKey,
+      userPda: walletPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+    const walletPdaData = async (): Promise<Buffer<ArrayBuffer>> => {
+      let walletPdaData = await program.account.data.fetch(walletPda);
+      return Buffer.from(walletPdaData.data);
+    }
+
+    await program.methods.update(Buffer.from(""), null).accounts(accounts).signers([wallet.payer]).rpc();
+    await program.methods.writeAt(0, Buffer.from("The quick brown fox")).accounts(accounts).signers([wallet.payer]).rpc();
+    await program.methods.writeAt(19, Buffer.from(" jumps over the lazy dog")).accounts(accounts).signers([wallet.payer]).rpc();
+    if (!(await walletPdaData()).equals(Buffer.from("The quick brown fox jumps over the lazy dog"))) throw new Error("mismatch");
+
+    // Overwrite in the middle without growing.
+    await program.methods.writeAt(4, Buffer.from("QUICK")).accounts(accounts).signers([wallet.payer]).rpc();
+    if (!(await walletPdaData()).equals(Buffer.from("The QUICK brown fox jumps over the lazy dog"))) throw new Error("mismatch");
+
+    await program.methods.truncate(9).accounts(accounts).signers([wallet.payer]).rpc();
+    if (!(await walletPdaData()).equals(Buffer.from("The QUICK"))) throw new Error("mismatch");
+
+    // Writing past the end would leave a gap.
+    let failed = false;
+    try {
+      await program.methods.writeAt(100, Buffer.from("gap")).accounts(accounts).signers([wallet.payer]).rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("write past the end should fail");
+
+    // Chunked writes leave the hash stale until the content is sealed.
+    let data = await program.account.data.fetch(walletPda);
+    if (!Buffer.from(data.hash).equals(Buffer.alloc(32))) throw new Error("hash should be cleared");
+    if (data.len !== 0) throw new Error("len should be cleared");
+    await program.methods.seal().accounts({ user: wallet.publicKey, userPda: walletPda }).signers([wallet.payer]).rpc();
+    const sealed = await program.account.data.fetch(walletPda);
+    const hash = require("crypto").createHash("sha256").update(Buffer.from("The QUICK")).digest();
+    if (!Buffer.from(sealed.hash).equals(hash)) throw new Error("hash mismatch");
+    if (sealed.len !== 9) throw new Error("len mismatch");
+    if (!sealed.version.eq(data.version)) throw new Error("seal should not change the version");
+  });
+
+  it("Close and init again", async () => {
+    const balance = await provider.connection.getBalance(wallet.publicKey);
+    await program.methods
+      .close()
+      .accounts({
+        user: wallet.publicKey,
+        userPda: walletPda,
+      })
+      .signers([wallet.payer])
+      .rpc();
+    if ((await provider.connection.getAccountInfo(walletPda)) !== null) throw new Error("account not closed");
+    if ((await provider.connection.getBalance(wallet.publicKey)) <= balance - 10000) throw new Error("rent not refunded");
+
+    await program.methods
+      .init()
+      .accounts({
+        user: wallet.publicKey,
+        userPda: walletPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([wallet.payer])
+      .rpc();
+    const walletPdaData = await program.account.data.fetch(walletPda);
+    if (walletPdaData.data.length !== 0) throw new Error("mismatch");
+  });
+
+  it("Named blobs and handing over auth", async () => {
+    const name = "poem";
+    const namedPda = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("seeds"), wallet.publicKey.toBuffer(), Buffer.from(name)],
+      program.programId
+    )[0] as anchor.web3.PublicKey;
+    await program.methods
+      .initNamed(name)
+      .accounts({
+        user: wallet.publicKey,
+        userPda: namedPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([wallet.payer])
+      .rpc();
+
+    const hotKey = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(await provider.connection.requestAirdrop(
+      hotKey.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    ), "confirmed");
+    await program.methods
+      .setAuth(hotKey.publicKey)
+      .accounts({
+        user: wallet.publicKey,
+        userPda: namedPda,
+      })
+      .signers([wallet.payer])
+      .rpc();
+
+    const poem = Buffer.from("The quick brown fox jumps over the lazy dog");
+    await program.methods
+      .update(poem, null)
+      .accounts({
+        user: hotKey.publicKey,
+        userPda: namedPda,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([hotKey])
+      .rpc();
+    if (!Buffer.from((await program.account.data.fetch(namedPda)).data).equals(poem)) throw new Error("mismatch");
+
+    // The creator no longer holds the write rights.
+    let failed = false;
+    try {
+      await program.methods
+        .update(Buffer.from(""), null)
+        .accounts({
+          user: wallet.publicKey,
+          userPda: namedPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([wallet.payer])
+        .rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("old auth should be rejected");
+
+    // An empty name would take the address of the user's default blob.
+    const other = anchor.web3.Keypair.generate();
+    await provider.connection.confirmTransaction(await provider.connection.requestAirdrop(
+      other.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    ), "confirmed");
+    const otherPda = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("seeds"), other.publicKey.toBuffer()],
+      program.programId
+    )[0] as anchor.web3.PublicKey;
+    failed = false;
+    try {
+      await program.methods
+        .initNamed("")
+        .accounts({
+          user: other.publicKey,
+          userPda: otherPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([other])
+        .rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("empty name should be rejected");
+    if ((await provider.connection.getAccountInfo(otherPda)) !== null) throw new Error("account should not exist");
+  });
+
+  it("Integrity metadata and expected version", async () => {
+    const accounts = {
+      user: wallet.publicKey,
+      userPda: walletPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+    const poem = Buffer.from("The quick brown fox jumps over the lazy dog");
+    const before = await program.account.data.fetch(walletPda);
+    await program.methods.update(poem, before.version).accounts(accounts).signers([wallet.payer]).rpc();
+
+    const after = await program.account.data.fetch(walletPda);
+    const hash = require("crypto").createHash("sha256").update(poem).digest();
+    if (!Buffer.from(after.hash).equals(hash)) throw new Error("hash mismatch");
+    if (after.len !== poem.length) throw new Error("len mismatch");
+    if (!after.version.eq(before.version.addn(1))) throw new Error("version mismatch");
+
+    // A writer holding the stale version is rejected.
+    let failed = false;
+    try {
+      await program.methods.update(Buffer.from(""), before.version).accounts(accounts).signers([wallet.payer]).rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("stale version should be rejected");
+  });
+
+  it("Grow, shrink and same-size updates leave the account exactly rent-exempt", async () => {
+    const accounts = {
+      user: wallet.publicKey,
+      userPda: walletPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+    const checkRentExempt = async () => {
+      const info = await provider.connection.getAccountInfo(walletPda);
+      const rentExemption = await provider.connection.getMinimumBalanceForRentExemption(info.data.length);
+      if (info.lamports !== rentExemption) throw new Error(`lamports ${info.lamports} != rent ${rentExemption}`);
+    };
+
+    // Grow.
+    await program.methods.update(Buffer.alloc(256, 1), null).accounts(accounts).signers([wallet.payer]).rpc();
+    await checkRentExempt();
+    // Same size.
+    await program.methods.update(Buffer.alloc(256, 2), null).accounts(accounts).signers([wallet.payer]).rpc();
+    await checkRentExempt();
+    // Shrink.
+    await program.methods.update(Buffer.alloc(16, 3), null).accounts(accounts).signers([wallet.payer]).rpc();
+    await checkRentExempt();
+
+    // Lamports sent by someone else are refunded as well.
+    const tx = new anchor.web3.Transaction().add(anchor.web3.SystemProgram.transfer({
+      fromPubkey: wallet.publicKey,
+      toPubkey: walletPda,
+      lamports: 1_000_000,
+    }));
+    await provider.sendAndConfirm(tx, [wallet.payer]);
+    await program.methods.update(Buffer.alloc(16, 4), null).accounts(accounts).signers([wallet.payer]).rpc();
+    await checkRentExempt();
+  });
+
+  it("Migrate, seal and close an account in the legacy layout", async () => {
+    // Preloaded from tests/fixtures/legacy-data.json, see Anchor.toml.
+    const legacyUser = anchor.web3.Keypair.fromSecretKey(Uint8Array.from([251, 128, 83, 209, 240, 67, 49, 172, 203, 115, 129, 209, 80, 71, 73, 70, 14, 84, 144, 119, 111, 0, 226, 254, 37, 97, 242, 205, 146, 59, 124, 104, 65, 122, 134, 47, 47, 7, 201, 1, 110, 70, 155, 120, 80, 92, 43, 212, 86, 171, 2, 239, 176, 146, 126, 93, 106, 62, 153, 142, 246, 58, 93, 53]));
+    const legacyPda = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("seeds"), legacyUser.publicKey.toBuffer()],
+      program.programId
+    )[0] as anchor.web3.PublicKey;
+    await provider.connection.confirmTransaction(await provider.connection.requestAirdrop(
+      legacyUser.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    ), "confirmed");
+    const accounts = {
+      user: legacyUser.publicKey,
+      userPda: legacyPda,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+
+    // The other instructions do not understand the legacy layout.
+    let failed = false;
+    try {
+      await program.methods.close().accounts({ user: legacyUser.publicKey, userPda: legacyPda }).signers([legacyUser]).rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("legacy account should be rejected before migrate");
+
+    await program.methods.migrate().accounts(accounts).signers([legacyUser]).rpc();
+    const poem = Buffer.from("The quick brown fox");
+    const migrated = await program.account.data.fetch(legacyPda);
+    if (!Buffer.from(migrated.data).equals(poem)) throw new Error("content lost");
+    if (!migrated.auth.equals(legacyUser.publicKey)) throw new Error("auth lost");
+    if (!Buffer.from(migrated.hash).equals(Buffer.alloc(32))) throw new Error("hash should be empty");
+    if (!migrated.version.eqn(1)) throw new Error("version mismatch");
+
+    // Migrating twice fails.
+    failed = false;
+    try {
+      await program.methods.migrate().accounts(accounts).signers([legacyUser]).rpc();
+    } catch (e) {
+      failed = true;
+    }
+    if (!failed) throw new Error("migrated account should be rejected");
+
+    await program.methods.seal().accounts({ user: legacyUser.publicKey, userPda: legacyPda }).signers([legacyUser]).rpc();
+    const hash = require("crypto").createHash("sha256").update(poem).digest();
+    if (!Buffer.from((await program.account.data.fetch(legacyPda)).hash).equals(hash)) throw new Error("hash mismatch");
+
+    await program.methods.close().accounts({ user: legacyUser.publicKey, userPda: legacyPda }).signers([legacyUser]).rpc();
+    if ((await provider.connection.getAccountInfo(legacyPda)) !== null) throw new Error("account not closed");
+  });
 });
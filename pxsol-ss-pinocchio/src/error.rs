@@ -0,0 +1,21 @@
+use pinocchio::error::ProgramError;
+
+/// 自定义错误码，与 pxsol-ss-anchor 的 `PxsolError` 保持一致（Anchor 的自定义错误从 6000 开始）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PxsolError {
+    /// 签名者不是数据账户记录的 auth
+    Unauthorized = 6000,
+    /// 账户数据或指令数据格式错误
+    InvalidData = 6001,
+    /// 偏移或长度超出已存储的数据
+    InvalidOffset = 6002,
+    /// 账户在 expected_version 之后被修改过
+    VersionMismatch = 6003,
+}
+
+impl From<PxsolError> for ProgramError {
+    fn from(e: PxsolError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
@@ -0,0 +1,37 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use super::helpers::DataAccounts;
+
+/// 删除数据账户并把全部租金退还给 user，之后可以重新 init
+pub struct Close<'a> {
+    pub accounts: DataAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Close<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = DataAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Close<'a> {
+    pub const DISCRIMINATOR: u8 = 6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let user = self.accounts.user;
+        let user_pda = self.accounts.user_pda;
+
+        user.set_lamports(
+            user.lamports()
+                .checked_add(user_pda.lamports())
+                .ok_or(ProgramError::ArithmeticOverflow)?,
+        );
+        // 清空 lamports、数据和 owner，账户交还给 System Program
+        user_pda.close()?;
+
+        Ok(())
+    }
+}
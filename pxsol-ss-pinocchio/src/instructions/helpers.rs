@@ -0,0 +1,158 @@
+use pinocchio::{
+    cpi::{Seed, Signer},
+    error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    AccountView, Address, ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+use crate::{Data, PxsolError, DATA_SEED};
+
+/// 修改已有数据账户的指令共用的账户：[user, user_pda, ..]
+/// 与 Anchor 版本一样不再由签名者重新推导种子，只检查账户记录的 auth，所以命名数据和转交过的数据也能写入
+pub struct DataAccounts<'a> {
+    pub user: &'a AccountView,
+    pub user_pda: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for DataAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [user, user_pda, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !user.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        // 只有本程序创建的账户才可能是数据账户
+        if !user_pda.owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !user_pda.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 签名者必须是数据账户记录的 auth
+        let data = user_pda.try_borrow_data()?;
+        let state = Data::load(&data)?;
+        if state.auth.ne(user.address()) {
+            return Err(PxsolError::Unauthorized.into());
+        }
+
+        Ok(Self { user, user_pda })
+    }
+}
+
+pub fn check_system_program(system_program: &AccountView) -> ProgramResult {
+    if system_program.address().ne(&pinocchio_system::ID) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+/// 创建 [b"seeds", user, name] 数据账户，name 为空时就是 [b"seeds", user]
+pub fn create_data_account(user: &AccountView, user_pda: &AccountView, name: &[u8]) -> ProgramResult {
+    if !user.is_signer() {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (user_pda_address, bump) =
+        Address::find_program_address(&[DATA_SEED, user.address().as_ref(), name], &crate::ID);
+    if user_pda_address.ne(user_pda.address()) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let bump = [bump];
+    let seeds = [
+        Seed::from(DATA_SEED),
+        Seed::from(user.address().as_ref()),
+        Seed::from(name),
+        Seed::from(&bump),
+    ];
+    let signers = [Signer::from(&seeds)];
+
+    let rent_exemption = Rent::get()?.minimum_balance(Data::LEN);
+    let lamports = user_pda.lamports();
+    if lamports == 0 {
+        CreateAccount {
+            from: user,
+            to: user_pda,
+            lamports: rent_exemption,
+            space: Data::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+    } else {
+        // 任何人都可以提前往 PDA 转入 lamports，这时 CreateAccount 会失败。只要账户仍归 System Program 所有，
+        // 就补足租金后 Allocate + Assign，和 Anchor 的 init 相同
+        if !user_pda.owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        if rent_exemption > lamports {
+            Transfer {
+                from: user,
+                to: user_pda,
+                lamports: rent_exemption - lamports,
+            }
+            .invoke()?;
+        }
+        Allocate {
+            account: user_pda,
+            space: Data::LEN as u64,
+        }
+        .invoke_signed(&signers)?;
+        Assign {
+            account: user_pda,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+    }
+
+    let slot = pinocchio::sysvars::clock::Clock::get()?.slot;
+    let mut data = user_pda.try_borrow_mut_data()?;
+    let state = Data::load_header_mut(&mut data)?;
+    state.set_inner(*user.address(), bump);
+    state.touch(&[], slot)?;
+
+    Ok(())
+}
+
+/// 把数据账户调整为能存放 data_len 字节内容。不足的租金由 user 补齐，
+/// 多出的租金直接从程序拥有的账户扣除退还给 user，和 pxsol-ss-anchor 的 `Data::resize` 相同
+pub fn resize(user: &AccountView, user_pda: &AccountView, data_len: usize) -> ProgramResult {
+    let space = Data::space_for(data_len);
+    let rent_exemption = Rent::get()?.minimum_balance(space);
+    let hold = user_pda.lamports();
+    if rent_exemption > hold {
+        Transfer {
+            from: user,
+            to: user_pda,
+            lamports: rent_exemption - hold,
+        }
+        .invoke()?;
+    }
+    if hold > rent_exemption {
+        let refund = hold - rent_exemption;
+        user_pda.set_lamports(rent_exemption);
+        user.set_lamports(user.lamports().checked_add(refund).ok_or(ProgramError::ArithmeticOverflow)?);
+    }
+    user_pda.resize(space)?;
+
+    let mut data = user_pda.try_borrow_mut_data()?;
+    Data::load_header_mut(&mut data[..Data::LEN])?.set_data_len(data_len);
+
+    Ok(())
+}
+
+/// 解析 4 字节小端序的 u32 参数，返回剩余的数据
+pub fn parse_u32(data: &[u8]) -> Result<(usize, &[u8]), ProgramError> {
+    if data.len() < 4 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let (head, rest) = data.split_at(4);
+    Ok((u32::from_le_bytes(head.try_into().unwrap()) as usize, rest))
+}
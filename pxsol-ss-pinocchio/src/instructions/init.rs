@@ -0,0 +1,45 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use super::helpers::{check_system_program, create_data_account};
+
+/// 创建 [b"seeds", user] 数据账户，内容为空
+pub struct InitAccounts<'a> {
+    pub user: &'a AccountView,
+    pub user_pda: &'a AccountView,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for InitAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let [user, user_pda, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_system_program(system_program)?;
+
+        Ok(Self { user, user_pda })
+    }
+}
+
+pub struct Init<'a> {
+    pub accounts: InitAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Init<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = InitAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Init<'a> {
+    pub const DISCRIMINATOR: u8 = 0;
+
+    pub fn process(&mut self) -> ProgramResult {
+        create_data_account(self.accounts.user, self.accounts.user_pda, &[])
+    }
+}
@@ -0,0 +1,49 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use super::helpers::create_data_account;
+use super::InitAccounts;
+
+/// 指令数据：名称字节，最长 32 字节（单个种子的长度上限）
+pub struct InitNamedInstructionData<'a> {
+    pub name: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitNamedInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.is_empty() || data.len() > 32 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { name: data })
+    }
+}
+
+/// 创建 [b"seeds", user, name] 数据账户，一个用户可以拥有多份命名数据
+pub struct InitNamed<'a> {
+    pub accounts: InitAccounts<'a>,
+    pub instruction_data: InitNamedInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for InitNamed<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = InitAccounts::try_from(accounts)?;
+        let instruction_data = InitNamedInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitNamed<'a> {
+    pub const DISCRIMINATOR: u8 = 1;
+
+    pub fn process(&mut self) -> ProgramResult {
+        create_data_account(self.accounts.user, self.accounts.user_pda, self.instruction_data.name)
+    }
+}
@@ -0,0 +1,25 @@
+pub mod helpers;
+
+pub mod init;
+pub use init::*;
+
+pub mod init_named;
+pub use init_named::*;
+
+pub mod set_auth;
+pub use set_auth::*;
+
+pub mod update;
+pub use update::*;
+
+pub mod write_at;
+pub use write_at::*;
+
+pub mod truncate;
+pub use truncate::*;
+
+pub mod close;
+pub use close::*;
+
+pub mod seal;
+pub use seal::*;
@@ -0,0 +1,31 @@
+use pinocchio::{error::ProgramError, AccountView, ProgramResult};
+
+use super::helpers::DataAccounts;
+use crate::Data;
+
+/// 分块上传结束后计算内容的哈希，内容、slot 和版本号都不变
+pub struct Seal<'a> {
+    pub accounts: DataAccounts<'a>,
+}
+
+impl<'a> TryFrom<&'a [AccountView]> for Seal<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountView]) -> Result<Self, Self::Error> {
+        let accounts = DataAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Seal<'a> {
+    pub const DISCRIMINATOR: u8 = 7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.user_pda.try_borrow_mut_data()?;
+        let (state, stored) = Data::load_mut(&mut data)?;
+        state.seal(stored);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, AccountView, Address, ProgramResult};
+
+use super::helpers::DataAccounts;
+use crate::Data;
+
+pub struct SetAuthInstructionData {
+    pub new_auth: Address,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetAuthInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Address>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let new_auth = Address::new_from_array(data.try_into().unwrap());
+
+        Ok(Self { new_auth })
+    }
+}
+
+/// 把写入权限转交给另一个账户，例如热钱包或其它程序的 PDA
+pub struct SetAuth<'a> {
+    pub accounts: DataAccounts<'a>,
+    pub instruction_data: SetAuthInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for SetAuth<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let accounts = DataAccounts::try_from(accounts)?;
+        let instruction_data = SetAuthInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetAuth<'a> {
+    pub const DISCRIMINATOR: u8 = 2;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.user_pda.try_borrow_mut_data()?;
+        let (state, _) = Data::load_mut(&mut data)?;
+        state.set_auth(self.instruction_data.new_auth);
+
+        Ok(())
+    }
+}
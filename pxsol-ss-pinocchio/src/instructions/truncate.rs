@@ -0,0 +1,70 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use super::helpers::{check_system_program, parse_u32, resize, DataAccounts};
+use crate::{Data, PxsolError};
+
+pub struct TruncateInstructionData {
+    pub len: usize,
+}
+
+impl<'a> TryFrom<&'a [u8]> for TruncateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (len, rest) = parse_u32(data)?;
+        if !rest.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { len })
+    }
+}
+
+/// 把内容截短到 len 字节，多出的租金退还给 user
+pub struct Truncate<'a> {
+    pub accounts: DataAccounts<'a>,
+    pub instruction_data: TruncateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Truncate<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [_, _, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_system_program(system_program)?;
+
+        let accounts = DataAccounts::try_from(accounts)?;
+        let instruction_data = TruncateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Truncate<'a> {
+    pub const DISCRIMINATOR: u8 = 5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let len = self.instruction_data.len;
+
+        if len > Data::load(&self.accounts.user_pda.try_borrow_data()?)?.data_len() {
+            return Err(PxsolError::InvalidOffset.into());
+        }
+        resize(self.accounts.user, self.accounts.user_pda, len)?;
+
+        let slot = Clock::get()?.slot;
+        let mut data = self.accounts.user_pda.try_borrow_mut_data()?;
+        let (state, _) = Data::load_mut(&mut data)?;
+        state.touch_chunk(slot)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use core::mem::size_of;
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use super::helpers::{check_system_program, resize, DataAccounts};
+use crate::{Data, PxsolError};
+
+/// 指令数据：expected_version (u64 小端序) + 新内容。
+/// 初始化后版本号从 1 开始，所以 expected_version 为 0 表示不检查版本
+pub struct UpdateInstructionData<'a> {
+    pub expected_version: u64,
+    pub content: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for UpdateInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() < size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (version, content) = data.split_at(size_of::<u64>());
+        let expected_version = u64::from_le_bytes(version.try_into().unwrap());
+
+        Ok(Self {
+            expected_version,
+            content,
+        })
+    }
+}
+
+/// 替换全部内容。内容直接从指令数据复制进账户，不经过 Vec<u8> 的反序列化和序列化
+pub struct Update<'a> {
+    pub accounts: DataAccounts<'a>,
+    pub instruction_data: UpdateInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for Update<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [_, _, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_system_program(system_program)?;
+
+        let accounts = DataAccounts::try_from(accounts)?;
+        let instruction_data = UpdateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Update<'a> {
+    pub const DISCRIMINATOR: u8 = 3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let content = self.instruction_data.content;
+
+        if self.instruction_data.expected_version != 0 {
+            let data = self.accounts.user_pda.try_borrow_data()?;
+            if Data::load(&data)?.version() != self.instruction_data.expected_version {
+                return Err(PxsolError::VersionMismatch.into());
+            }
+        }
+
+        resize(self.accounts.user, self.accounts.user_pda, content.len())?;
+
+        let slot = Clock::get()?.slot;
+        let mut data = self.accounts.user_pda.try_borrow_mut_data()?;
+        let (state, stored) = Data::load_mut(&mut data)?;
+        stored.copy_from_slice(content);
+        state.touch(stored, slot)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+use pinocchio::{
+    error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    AccountView, ProgramResult,
+};
+
+use super::helpers::{check_system_program, parse_u32, resize, DataAccounts};
+use crate::{Data, PxsolError};
+
+/// 指令数据：offset (u32 小端序) + 要写入的字节
+pub struct WriteAtInstructionData<'a> {
+    pub offset: usize,
+    pub bytes: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for WriteAtInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let (offset, bytes) = parse_u32(data)?;
+
+        Ok(Self { offset, bytes })
+    }
+}
+
+/// 从 offset 开始写入，超出当前长度时扩容。不允许留下空洞，大数据分多笔交易连续写入
+pub struct WriteAt<'a> {
+    pub accounts: DataAccounts<'a>,
+    pub instruction_data: WriteAtInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountView])> for WriteAt<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountView])) -> Result<Self, Self::Error> {
+        let [_, _, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        check_system_program(system_program)?;
+
+        let accounts = DataAccounts::try_from(accounts)?;
+        let instruction_data = WriteAtInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> WriteAt<'a> {
+    pub const DISCRIMINATOR: u8 = 4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let offset = self.instruction_data.offset;
+        let bytes = self.instruction_data.bytes;
+
+        let len = Data::load(&self.accounts.user_pda.try_borrow_data()?)?.data_len();
+        if offset > len {
+            return Err(PxsolError::InvalidOffset.into());
+        }
+        let end = offset.checked_add(bytes.len()).ok_or(PxsolError::InvalidOffset)?;
+        if end > u32::MAX as usize {
+            return Err(PxsolError::InvalidOffset.into());
+        }
+        if end > len {
+            resize(self.accounts.user, self.accounts.user_pda, end)?;
+        }
+
+        let slot = Clock::get()?.slot;
+        let mut data = self.accounts.user_pda.try_borrow_mut_data()?;
+        let (state, stored) = Data::load_mut(&mut data)?;
+        stored[offset..end].copy_from_slice(bytes);
+        state.touch_chunk(slot)?;
+
+        Ok(())
+    }
+}
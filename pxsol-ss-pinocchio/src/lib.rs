@@ -0,0 +1,53 @@
+#![no_std]
+
+use pinocchio::{entrypoint, error::ProgramError, nostd_panic_handler, AccountView, Address, ProgramResult};
+use solana_address::declare_id;
+
+pub mod error;
+pub use error::*;
+
+pub mod instructions;
+pub use instructions::*;
+
+pub mod state;
+pub use state::*;
+
+entrypoint!(process_instruction);
+nostd_panic_handler!();
+
+declare_id!("22222222222222222222222222222222222222222222");
+
+/// 主入口函数：按第一个字节分发指令
+fn process_instruction(
+    _program_id: &Address,
+    accounts: &[AccountView],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match instruction_data.split_first() {
+        Some((discriminator, _)) if *discriminator == Init::DISCRIMINATOR => {
+            Init::try_from(accounts)?.process()
+        }
+        Some((discriminator, data)) if *discriminator == InitNamed::DISCRIMINATOR => {
+            InitNamed::try_from((data, accounts))?.process()
+        }
+        Some((discriminator, data)) if *discriminator == SetAuth::DISCRIMINATOR => {
+            SetAuth::try_from((data, accounts))?.process()
+        }
+        Some((discriminator, data)) if *discriminator == Update::DISCRIMINATOR => {
+            Update::try_from((data, accounts))?.process()
+        }
+        Some((discriminator, data)) if *discriminator == WriteAt::DISCRIMINATOR => {
+            WriteAt::try_from((data, accounts))?.process()
+        }
+        Some((discriminator, data)) if *discriminator == Truncate::DISCRIMINATOR => {
+            Truncate::try_from((data, accounts))?.process()
+        }
+        Some((discriminator, _)) if *discriminator == Close::DISCRIMINATOR => {
+            Close::try_from(accounts)?.process()
+        }
+        Some((discriminator, _)) if *discriminator == Seal::DISCRIMINATOR => {
+            Seal::try_from(accounts)?.process()
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
@@ -0,0 +1,129 @@
+use core::mem::size_of;
+use pinocchio::{error::ProgramError, Address};
+
+use crate::PxsolError;
+
+/// 数据账户 PDA 的种子前缀，完整种子为 [b"seeds", user] 或 [b"seeds", user, name]，与 pxsol-ss-anchor 相同
+pub const DATA_SEED: &[u8] = b"seeds";
+
+/// Anchor 账户判别符：sha256("account:Data") 的前 8 字节
+pub const DATA_DISCRIMINATOR: [u8; 8] = [206, 156, 59, 188, 18, 79, 240, 232];
+
+/// 数据账户的头部，布局与 pxsol-ss-anchor 的 `Data` 经 Borsh 序列化后的结果一致，
+/// 头部之后紧跟 `data_len` 个字节的内容。所有字段都是字节数组，按 1 字节对齐，可以直接从账户数据转换。
+#[repr(C)]
+pub struct Data {
+    pub discriminator: [u8; 8], // Anchor 账户判别符
+    pub auth: Address,          // 允许写入的账户
+    pub bump: [u8; 1],          // PDA bump seed
+    pub hash: [u8; 32],         // 内容的 sha256，分块写入后、seal 之前为全零
+    pub len: [u8; 4],           // hash 覆盖的内容长度，u32 小端序
+    pub slot: [u8; 8],          // 最后修改的 slot，u64 小端序
+    pub version: [u8; 8],       // 每次修改加一，u64 小端序
+    pub data_len: [u8; 4],      // Borsh Vec<u8> 的长度前缀，u32 小端序
+}
+
+impl Data {
+    pub const LEN: usize = size_of::<[u8; 8]>()
+        + size_of::<Address>()
+        + size_of::<[u8; 1]>()
+        + size_of::<[u8; 32]>()
+        + size_of::<[u8; 4]>()
+        + size_of::<[u8; 8]>()
+        + size_of::<[u8; 8]>()
+        + size_of::<[u8; 4]>();
+
+    /// 存放 data_len 字节内容所需的账户空间
+    #[inline(always)]
+    pub fn space_for(data_len: usize) -> usize {
+        Data::LEN + data_len
+    }
+
+    /// 只读取头部，同时检查判别符和长度前缀
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < Data::LEN {
+            return Err(PxsolError::InvalidData.into());
+        }
+        let data = unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) };
+        if data.discriminator != DATA_DISCRIMINATOR || Data::space_for(data.data_len()) != bytes.len() {
+            return Err(PxsolError::InvalidData.into());
+        }
+        Ok(data)
+    }
+
+    /// 把账户数据拆成头部和内容两部分，内容按字节原地读写，不做反序列化
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<(&mut Self, &mut [u8]), ProgramError> {
+        Data::load(bytes)?;
+        let (header, content) = bytes.split_at_mut(Data::LEN);
+        Ok((unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(header.as_mut_ptr()) }, content))
+    }
+
+    /// 不检查判别符和长度前缀，用于新建账户和调整账户大小之后
+    #[inline(always)]
+    pub fn load_header_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != Data::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
+    }
+
+    #[inline(always)]
+    pub fn data_len(&self) -> usize {
+        u32::from_le_bytes(self.data_len) as usize
+    }
+
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        u64::from_le_bytes(self.version)
+    }
+
+    #[inline(always)]
+    pub fn set_auth(&mut self, auth: Address) {
+        self.auth = auth;
+    }
+
+    #[inline(always)]
+    pub fn set_data_len(&mut self, data_len: usize) {
+        self.data_len = (data_len as u32).to_le_bytes();
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, auth: Address, bump: [u8; 1]) {
+        self.discriminator = DATA_DISCRIMINATOR;
+        self.auth = auth;
+        self.bump = bump;
+        self.data_len = [0; 4];
+    }
+
+    /// 内容修改后更新哈希、长度、slot 和版本号
+    #[inline(always)]
+    pub fn touch(&mut self, content: &[u8], slot: u64) -> Result<(), ProgramError> {
+        let version = self.version().checked_add(1).ok_or(PxsolError::InvalidData)?;
+        self.hash = solana_sha256_hasher::hash(content).to_bytes();
+        self.len = (content.len() as u32).to_le_bytes();
+        self.slot = slot.to_le_bytes();
+        self.version = version.to_le_bytes();
+        Ok(())
+    }
+
+    /// 分块写入后更新 slot 和版本号。每写一块都对整个内容做哈希会让上传的开销随块数平方增长，
+    /// 所以这里只清空哈希和长度，由 seal 在上传结束后计算一次
+    #[inline(always)]
+    pub fn touch_chunk(&mut self, slot: u64) -> Result<(), ProgramError> {
+        let version = self.version().checked_add(1).ok_or(PxsolError::InvalidData)?;
+        self.hash = [0; 32];
+        self.len = [0; 4];
+        self.slot = slot.to_le_bytes();
+        self.version = version.to_le_bytes();
+        Ok(())
+    }
+
+    /// 计算内容的哈希和长度，不改变 slot 和版本号
+    #[inline(always)]
+    pub fn seal(&mut self, content: &[u8]) {
+        self.hash = solana_sha256_hasher::hash(content).to_bytes();
+        self.len = (content.len() as u32).to_le_bytes();
+    }
+}
@@ -0,0 +1,217 @@
+// 对比 pxsol-ss-pinocchio 与 pxsol-ss-anchor 的计算单元（CU）消耗。
+// 运行前先构建两个程序，并把 Anchor 版本的 .so 复制到本 crate 的 tests/fixtures 下：
+//   (cd ../pxsol-ss-anchor && anchor build)
+//   mkdir -p tests/fixtures && cp ../pxsol-ss-anchor/target/deploy/pxsol_ss_anchor.so tests/fixtures/
+//   cargo build-sbf && cargo test-sbf -- --nocapture
+use solana_program_test::*;
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+// 单笔交易最多 1232 字节，内容按块写入
+const CHUNK: usize = 900;
+// 写入后账户里已有的数据量，Anchor 版本每次 update 都要反序列化这么多字节
+const BLOB: usize = 9 * CHUNK;
+
+fn pinocchio_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn anchor_id() -> Pubkey {
+    Pubkey::from_str("HCMXPF8jiyJsBURriezgnUYMAigUXMN8RNy7zgGPDgMQ").unwrap()
+}
+
+fn data_pda(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"seeds", user.as_ref()], program_id).0
+}
+
+// 两个程序的指令编码不同：Pinocchio 版本用 1 字节判别符，Anchor 版本用 sha256("global:<name>") 的前 8 字节加 Borsh 参数
+trait Encoder {
+    fn program_id(&self) -> Pubkey;
+    fn init(&self) -> Vec<u8>;
+    fn update(&self, content: &[u8]) -> Vec<u8>;
+    fn write_at(&self, offset: u32, bytes: &[u8]) -> Vec<u8>;
+    fn seal(&self) -> Vec<u8>;
+}
+
+struct Pinocchio;
+
+impl Encoder for Pinocchio {
+    fn program_id(&self) -> Pubkey {
+        pinocchio_id()
+    }
+
+    fn init(&self) -> Vec<u8> {
+        vec![0]
+    }
+
+    fn update(&self, content: &[u8]) -> Vec<u8> {
+        let mut data = vec![3];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(content);
+        data
+    }
+
+    fn write_at(&self, offset: u32, bytes: &[u8]) -> Vec<u8> {
+        let mut data = vec![4];
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(bytes);
+        data
+    }
+
+    fn seal(&self) -> Vec<u8> {
+        vec![7]
+    }
+}
+
+struct Anchor;
+
+impl Anchor {
+    fn discriminator(name: &str) -> Vec<u8> {
+        hash(format!("global:{}", name).as_bytes()).to_bytes()[..8].to_vec()
+    }
+}
+
+impl Encoder for Anchor {
+    fn program_id(&self) -> Pubkey {
+        anchor_id()
+    }
+
+    fn init(&self) -> Vec<u8> {
+        Anchor::discriminator("init")
+    }
+
+    fn update(&self, content: &[u8]) -> Vec<u8> {
+        let mut data = Anchor::discriminator("update");
+        data.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        data.extend_from_slice(content);
+        data.push(0); // expected_version: None
+        data
+    }
+
+    fn write_at(&self, offset: u32, bytes: &[u8]) -> Vec<u8> {
+        let mut data = Anchor::discriminator("write_at");
+        data.extend_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        data.extend_from_slice(bytes);
+        data
+    }
+
+    fn seal(&self) -> Vec<u8> {
+        Anchor::discriminator("seal")
+    }
+}
+
+async fn start() -> ProgramTestContext {
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("pxsol_ss_pinocchio", pinocchio_id(), None);
+    program_test.add_program("pxsol_ss_anchor", anchor_id(), None);
+    program_test.start_with_context().await
+}
+
+// 执行指令并返回消耗的 CU
+async fn run(context: &mut ProgramTestContext, encoder: &dyn Encoder, data: Vec<u8>) -> u64 {
+    let user = context.payer.pubkey();
+    let instruction = Instruction {
+        program_id: encoder.program_id(),
+        accounts: vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(data_pda(&encoder.program_id(), &user), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data,
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&user),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation = context
+        .banks_client
+        .simulate_transaction(transaction.clone())
+        .await
+        .unwrap();
+    let units = simulation.simulation_details.unwrap().units_consumed;
+    context.banks_client.process_transaction(transaction).await.unwrap();
+    units
+}
+
+// 同样的操作序列在两个程序上各执行一次，返回每一步的 CU
+async fn bench(context: &mut ProgramTestContext, encoder: &dyn Encoder) -> Vec<(String, u64)> {
+    let mut result = vec![];
+    result.push(("init".to_string(), run(context, encoder, encoder.init()).await));
+    for size in [32, 256, CHUNK] {
+        let units = run(context, encoder, encoder.update(&vec![size as u8; size])).await;
+        result.push((format!("update {} bytes", size), units));
+    }
+    for i in 0..BLOB / CHUNK - 1 {
+        let offset = CHUNK * (i + 1);
+        let units = run(context, encoder, encoder.write_at(offset as u32, &vec![i as u8; CHUNK])).await;
+        result.push((format!("write_at {}..{}", offset, offset + CHUNK), units));
+    }
+    let units = run(context, encoder, encoder.write_at(0, &[0xff; 32])).await;
+    result.push((format!("write_at 32 bytes into {} bytes", BLOB), units));
+    let units = run(context, encoder, encoder.seal()).await;
+    result.push((format!("seal {} bytes", BLOB), units));
+    let units = run(context, encoder, encoder.update(&[0xee; 32])).await;
+    result.push((format!("update {} bytes -> 32 bytes", BLOB), units));
+    result
+}
+
+#[tokio::test]
+async fn test_compute_units() {
+    let mut context = start().await;
+    let pinocchio = bench(&mut context, &Pinocchio).await;
+    let anchor = bench(&mut context, &Anchor).await;
+
+    println!("{:<40} {:>10} {:>10}", "instruction", "pinocchio", "anchor");
+    for ((name, p), (_, a)) in pinocchio.iter().zip(anchor.iter()) {
+        println!("{:<40} {:>10} {:>10}", name, p, a);
+    }
+    for ((name, p), (_, a)) in pinocchio.iter().zip(anchor.iter()) {
+        assert!(p < a, "{}: pinocchio {} >= anchor {}", name, p, a);
+    }
+}
+
+#[tokio::test]
+async fn test_layout_is_compatible() {
+    let mut context = start().await;
+    let user = context.payer.pubkey();
+    for encoder in [&Pinocchio as &dyn Encoder, &Anchor] {
+        run(&mut context, encoder, encoder.init()).await;
+        run(&mut context, encoder, encoder.update(b"The quick brown fox")).await;
+        run(&mut context, encoder, encoder.write_at(19, b" jumps over the lazy dog")).await;
+        run(&mut context, encoder, encoder.seal()).await;
+    }
+
+    let mut pinocchio = context
+        .banks_client
+        .get_account(data_pda(&pinocchio_id(), &user))
+        .await
+        .unwrap()
+        .unwrap();
+    let mut anchor = context
+        .banks_client
+        .get_account(data_pda(&anchor_id(), &user))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(pinocchio.lamports, anchor.lamports);
+
+    // bump 随程序地址不同，slot 随执行时间不同，其余字节必须完全一致
+    for data in [&mut pinocchio.data, &mut anchor.data] {
+        data[40] = 0;
+        data[77..85].fill(0);
+    }
+    assert_eq!(pinocchio.data, anchor.data);
+    assert_eq!(pinocchio.data[41..73], hash(b"The quick brown fox jumps over the lazy dog").to_bytes());
+}
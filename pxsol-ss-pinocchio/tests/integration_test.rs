@@ -0,0 +1,98 @@
+// 运行前先构建 SBF 程序：cargo build-sbf && cargo test-sbf
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    transaction::{Transaction, TransactionError},
+};
+use std::str::FromStr;
+
+fn program_id() -> Pubkey {
+    Pubkey::from_str("22222222222222222222222222222222222222222222").unwrap()
+}
+
+fn data_pda(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"seeds", user.as_ref()], &program_id()).0
+}
+
+fn init_instruction(user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(data_pda(user), false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: vec![0],
+    }
+}
+
+fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("pxsol_ss_pinocchio", program_id(), None);
+    program_test.prefer_bpf(true);
+    program_test
+}
+
+async fn init(context: &mut ProgramTestContext) -> Result<(), BanksClientError> {
+    let transaction = Transaction::new_signed_with_payer(
+        &[init_instruction(&context.payer.pubkey())],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(transaction).await
+}
+
+#[tokio::test]
+async fn test_init_prefunded_pda() {
+    let mut program_test = program_test();
+    let user = Keypair::new();
+    program_test.add_account(
+        user.pubkey(),
+        Account {
+            lamports: 10_000_000_000,
+            owner: system_program::ID,
+            ..Account::default()
+        },
+    );
+    // 任何人都可以提前往 PDA 转账，这不能让 init 永远失败
+    program_test.add_account(
+        data_pda(&user.pubkey()),
+        Account {
+            lamports: 1,
+            owner: system_program::ID,
+            ..Account::default()
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    context.payer = user;
+
+    init(&mut context).await.unwrap();
+
+    let account = context.banks_client.get_account(data_pda(&context.payer.pubkey())).await.unwrap().unwrap();
+    assert_eq!(account.owner, program_id());
+    assert!(Rent::default().is_exempt(account.lamports, account.data.len()));
+}
+
+#[tokio::test]
+async fn test_init_twice_is_rejected() {
+    let mut context = program_test().start_with_context().await;
+
+    init(&mut context).await.unwrap();
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let result = init(&mut context).await;
+
+    assert_eq!(
+        result.unwrap_err().unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountAlreadyInitialized)
+    );
+}